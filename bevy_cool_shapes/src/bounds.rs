@@ -0,0 +1,354 @@
+//! Bounding volumes of shapes, in the shape's local space.
+//!
+//! 2d shapes lie in the XY plane, their [`Aabb`] has no depth.
+
+use bevy_math::{Mat4, Vec2, Vec3};
+
+use crate::*;
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+impl Aabb {
+    /// The smallest box containing all `points`, an empty box at the origin
+    /// if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Self {
+        let mut points = points.into_iter();
+        let first = points.next().unwrap_or(Vec3::ZERO);
+        let init = Aabb { min: first, max: first };
+        points.fold(init, |Aabb { min, max }, p| Aabb {
+            min: min.min(p),
+            max: max.max(p),
+        })
+    }
+    /// A box centered on the origin.
+    pub fn from_half_extents(half_extents: Vec3) -> Self {
+        Aabb { min: -half_extents, max: half_extents }
+    }
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) / 2.0
+    }
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) / 2.0
+    }
+    /// The smallest box containing both `self` and `other`.
+    pub fn merge(&self, other: &Aabb) -> Self {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+    /// The smallest axis-aligned box containing this box once transformed.
+    ///
+    /// Use `Transform::compute_matrix` to get `transform` from a bevy
+    /// `Transform`.
+    pub fn transformed(&self, transform: Mat4) -> Self {
+        let center = transform.transform_point3(self.center());
+        let half_extents = self.half_extents();
+        let axes = [transform.x_axis, transform.y_axis, transform.z_axis];
+        let [x, y, z] = axes.map(|axis| axis.truncate().abs());
+        let half_extents = x * half_extents.x + y * half_extents.y + z * half_extents.z;
+        Aabb::from_half_extents(half_extents).translated(center)
+    }
+    fn translated(&self, offset: Vec3) -> Self {
+        Aabb { min: self.min + offset, max: self.max + offset }
+    }
+}
+
+/// A sphere containing a shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+impl BoundingSphere {
+    /// A sphere containing all `points`, centered on their bounding box.
+    ///
+    /// This is not the smallest sphere possible.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3> + Clone) -> Self {
+        let center = Aabb::from_points(points.clone()).center();
+        let distance = |p: Vec3| p.distance(center);
+        let radius = points.into_iter().map(distance).fold(0.0, f32::max);
+        BoundingSphere { center, radius }
+    }
+    /// The sphere containing `aabb`.
+    pub fn from_aabb(aabb: &Aabb) -> Self {
+        let radius = aabb.half_extents().length();
+        BoundingSphere { center: aabb.center(), radius }
+    }
+    /// A sphere containing this sphere once transformed.
+    ///
+    /// With non-uniform scaling, the radius is scaled by the largest factor.
+    pub fn transformed(&self, transform: Mat4) -> Self {
+        let center = transform.transform_point3(self.center);
+        let axes = [transform.x_axis, transform.y_axis, transform.z_axis];
+        let scale = axes.map(|axis| axis.truncate().length());
+        let max_scale = scale.into_iter().fold(0.0, f32::max);
+        BoundingSphere { center, radius: self.radius * max_scale }
+    }
+}
+
+fn z3d(v: Vec2) -> Vec3 {
+    v.extend(0.0)
+}
+fn centered(radius: f32) -> BoundingSphere {
+    BoundingSphere { center: Vec3::ZERO, radius }
+}
+/// The 2d bounding box of `shape`, from its support points.
+fn base_bounds(shape: &impl Shape2dBase) -> (Vec2, Vec2) {
+    let max = Vec2::new(shape.support(Vec2::X).x, shape.support(Vec2::Y).y);
+    let min = Vec2::new(shape.support(-Vec2::X).x, shape.support(-Vec2::Y).y);
+    (min, max)
+}
+
+impl Disc {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(Vec3::new(self.radius, self.radius, 0.0))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.radius)
+    }
+}
+impl HalfDisc {
+    pub fn aabb(&self) -> Aabb {
+        let r = self.radius;
+        Aabb {
+            min: Vec3::new(-r, 0.0, 0.0),
+            max: Vec3::new(r, r, 0.0),
+        }
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.radius)
+    }
+}
+impl QuarterDisc {
+    pub fn aabb(&self) -> Aabb {
+        let r = self.radius;
+        Aabb { min: Vec3::ZERO, max: Vec3::new(r, r, 0.0) }
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let center = Vec3::new(1.0, 1.0, 0.0) * self.radius / 2.0;
+        BoundingSphere { center, radius: self.radius / 2.0_f32.sqrt() }
+    }
+}
+impl Annulus {
+    pub fn aabb(&self) -> Aabb {
+        let r = self.outer_radius;
+        Aabb::from_half_extents(Vec3::new(r, r, 0.0))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.outer_radius)
+    }
+}
+impl Ellipse {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(z3d(self.half_axes))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.half_axes.max_element())
+    }
+}
+impl Rectangle {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(z3d(self.size / 2.0))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.size.length() / 2.0)
+    }
+}
+impl RoundedRectangle {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(z3d(self.size / 2.0))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let inner = self.size / 2.0 - self.bevel;
+        centered(inner.length() + self.bevel)
+    }
+}
+impl Triangle {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points([self.a, self.b, self.c].map(z3d))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::from_points([self.a, self.b, self.c].map(z3d))
+    }
+}
+impl Polygon {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.points.iter().copied().map(z3d))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::from_points(self.points.iter().copied().map(z3d))
+    }
+}
+impl Shape2d {
+    pub fn aabb(&self) -> Aabb {
+        match self {
+            Shape2d::Disc(shape) => shape.aabb(),
+            Shape2d::HalfDisc(shape) => shape.aabb(),
+            Shape2d::QuarterDisc(shape) => shape.aabb(),
+            Shape2d::Annulus(shape) => shape.aabb(),
+            Shape2d::Ellipse(shape) => shape.aabb(),
+            Shape2d::Rectangle(shape) => shape.aabb(),
+            Shape2d::RoundedRectangle(shape) => shape.aabb(),
+            Shape2d::Triangle(shape) => shape.aabb(),
+            Shape2d::Polygon(shape) => shape.aabb(),
+        }
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        match self {
+            Shape2d::Disc(shape) => shape.bounding_sphere(),
+            Shape2d::HalfDisc(shape) => shape.bounding_sphere(),
+            Shape2d::QuarterDisc(shape) => shape.bounding_sphere(),
+            Shape2d::Annulus(shape) => shape.bounding_sphere(),
+            Shape2d::Ellipse(shape) => shape.bounding_sphere(),
+            Shape2d::Rectangle(shape) => shape.bounding_sphere(),
+            Shape2d::RoundedRectangle(shape) => shape.bounding_sphere(),
+            Shape2d::Triangle(shape) => shape.bounding_sphere(),
+            Shape2d::Polygon(shape) => shape.bounding_sphere(),
+        }
+    }
+}
+
+impl<B: Shape2dBase> Pyramid<B> {
+    pub fn aabb(&self) -> Aabb {
+        let (min, max) = base_bounds(&self.base);
+        let half_height = self.height / 2.0;
+        let base = Aabb {
+            min: Vec3::new(min.x, -half_height, min.y),
+            max: Vec3::new(max.x, -half_height, max.y),
+        };
+        base.merge(&Aabb::from_points([Vec3::Y * half_height]))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::from_aabb(&self.aabb())
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    pub fn aabb(&self) -> Aabb {
+        let (min, max) = base_bounds(&self.base);
+        let half_height = self.height / 2.0;
+        Aabb {
+            min: Vec3::new(min.x, -half_height, min.y),
+            max: Vec3::new(max.x, half_height, max.y),
+        }
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::from_aabb(&self.aabb())
+    }
+}
+impl Sphere {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(Vec3::splat(self.radius))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.radius)
+    }
+}
+impl HalfSphere {
+    pub fn aabb(&self) -> Aabb {
+        let r = self.radius;
+        Aabb { min: Vec3::new(-r, 0.0, -r), max: Vec3::splat(r) }
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.radius)
+    }
+}
+impl Capsule {
+    pub fn aabb(&self) -> Aabb {
+        let Capsule { radius, segment_height } = *self;
+        Aabb::from_half_extents(Vec3::new(radius, segment_height / 2.0 + radius, radius))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.segment_height / 2.0 + self.radius)
+    }
+}
+impl Cuboid {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(self.size / 2.0)
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.size.length() / 2.0)
+    }
+}
+impl RoundedCuboid {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(self.size / 2.0)
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let inner = self.size / 2.0 - self.bevel;
+        centered(inner.length() + self.bevel)
+    }
+}
+impl Cone {
+    pub fn aabb(&self) -> Aabb {
+        let Cone { height, base_radius } = *self;
+        Aabb::from_half_extents(Vec3::new(base_radius, height / 2.0, base_radius))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(Vec2::new(self.base_radius, self.height / 2.0).length())
+    }
+}
+impl Tetrahedron {
+    pub fn aabb(&self) -> Aabb {
+        let Tetrahedron { a, b, c, d } = *self;
+        Aabb::from_points([a, b, c, d])
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let Tetrahedron { a, b, c, d } = *self;
+        BoundingSphere::from_points([a, b, c, d])
+    }
+}
+impl Lines {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.points.iter().copied())
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::from_points(self.points.iter().copied())
+    }
+}
+impl Cylinder {
+    pub fn aabb(&self) -> Aabb {
+        let Cylinder { height, radius } = *self;
+        Aabb::from_half_extents(Vec3::new(radius, height / 2.0, radius))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(Vec2::new(self.radius, self.height / 2.0).length())
+    }
+}
+impl Torus {
+    pub fn aabb(&self) -> Aabb {
+        let Torus { major_radius, minor_radius } = *self;
+        let outer = major_radius + minor_radius;
+        Aabb::from_half_extents(Vec3::new(outer, minor_radius, outer))
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.major_radius + self.minor_radius)
+    }
+}
+impl Ellipsoid {
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_half_extents(self.half_axes)
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        centered(self.half_axes.max_element())
+    }
+}
+impl HeightField {
+    pub fn aabb(&self) -> Aabb {
+        let heights = self.heights.iter().flatten().copied();
+        let min_y = heights.clone().fold(f32::INFINITY, f32::min);
+        let max_y = heights.fold(f32::NEG_INFINITY, f32::max);
+        let half_size = self.size / 2.0;
+        Aabb {
+            min: Vec3::new(-half_size.x, min_y, -half_size.y),
+            max: Vec3::new(half_size.x, max_y, half_size.y),
+        }
+    }
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::from_aabb(&self.aabb())
+    }
+}
@@ -0,0 +1,103 @@
+//! Point containment tests for 3d shapes.
+//!
+//! 2d shapes implement it with [`Shape2dBase::contains`].
+
+use bevy_math::{Vec2, Vec3, Vec3Swizzles};
+
+use crate::*;
+
+impl<B: Shape2dBase> Pyramid<B> {
+    /// Whether `point` is inside the pyramid or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let half_height = self.height / 2.0;
+        if point.y.abs() > half_height {
+            return false;
+        }
+        // How much the base is shrunk at the height of `point`.
+        let scale = (half_height - point.y) / self.height;
+        if scale == 0.0 {
+            return point.xz() == Vec2::ZERO;
+        }
+        self.base.contains(point.xz() / scale)
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    /// Whether `point` is inside the extruded shape or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.y.abs() <= self.height / 2.0 && self.base.contains(point.xz())
+    }
+}
+impl Sphere {
+    /// Whether `point` is inside the sphere or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.length_squared() <= self.radius * self.radius
+    }
+}
+impl HalfSphere {
+    /// Whether `point` is inside the half sphere or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.y >= 0.0 && point.length_squared() <= self.radius * self.radius
+    }
+}
+impl Capsule {
+    /// Whether `point` is inside the capsule or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let half_segment = self.segment_height / 2.0;
+        let on_segment = Vec3::Y * point.y.clamp(-half_segment, half_segment);
+        point.distance_squared(on_segment) <= self.radius * self.radius
+    }
+}
+impl Cuboid {
+    /// Whether `point` is inside the cuboid or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.abs().cmple(self.size / 2.0).all()
+    }
+}
+impl RoundedCuboid {
+    /// Whether `point` is inside the cuboid or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let outside_inner = (point.abs() - (self.size / 2.0 - self.bevel)).max(Vec3::ZERO);
+        outside_inner.length_squared() <= self.bevel * self.bevel
+    }
+}
+impl Cone {
+    /// Whether `point` is inside the cone or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let half_height = self.height / 2.0;
+        let radius = self.base_radius * (half_height - point.y) / self.height;
+        point.y.abs() <= half_height && point.xz().length_squared() <= radius * radius
+    }
+}
+impl Tetrahedron {
+    /// Whether `point` is inside the tetrahedron or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let Tetrahedron { a, b, c, d } = *self;
+        // `point` must be on the same side of each face as the opposite corner.
+        let same_side = |[a, b, c, opposite]: [Vec3; 4]| {
+            let normal = (b - a).cross(c - a);
+            normal.dot(point - a) * normal.dot(opposite - a) >= 0.0
+        };
+        let faces = [[a, b, c, d], [a, b, d, c], [a, c, d, b], [b, c, d, a]];
+        faces.into_iter().all(same_side)
+    }
+}
+impl Cylinder {
+    /// Whether `point` is inside the cylinder or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let in_height = point.y.abs() <= self.height / 2.0;
+        in_height && point.xz().length_squared() <= self.radius * self.radius
+    }
+}
+impl Ellipsoid {
+    /// Whether `point` is inside the ellipsoid or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        (point / self.half_axes).length_squared() <= 1.0
+    }
+}
+impl Torus {
+    /// Whether `point` is inside the torus or on its surface.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let from_tube = Vec2::new(point.xz().length() - self.major_radius, point.y);
+        from_tube.length_squared() <= self.minor_radius * self.minor_radius
+    }
+}
@@ -1,114 +1,181 @@
 use bevy_math::{Vec2, Vec3};
+#[cfg(feature = "reflect")]
+use bevy_reflect::{FromReflect, Reflect};
+
+mod bounds;
+mod contains;
+mod measure;
+mod polygon;
+pub mod query;
+mod ray;
+#[cfg(feature = "reflect")]
+mod reflect;
+#[cfg(feature = "rand")]
+mod sample;
+mod sdf;
+mod shape2d;
+mod validate;
+
+pub use bounds::{Aabb, BoundingSphere};
+pub use ray::{RayHit, RayHit2d};
+#[cfg(feature = "reflect")]
+pub use reflect::register_types;
+pub use shape2d::Shape2dBase;
+pub use validate::ShapeError;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Disc {
     pub radius: f32,
 }
 /// Upper half of a circle.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct HalfDisc {
     pub radius: f32,
 }
 /// +x/+y quadrant of a circle.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct QuarterDisc {
     pub radius: f32,
 }
+/// Ring between two concentric circles.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+pub struct Annulus {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+}
+/// Circle stretched along the X and Y axes by `half_axes`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+pub struct Ellipse {
+    pub half_axes: Vec2,
+}
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Rectangle {
     pub size: Vec2,
 }
-// /// Rectangle of size `size` with bevelled edge.
-// #[derive(Debug, Clone)]
-// pub struct RoundedRectangle {
-//     pub size: Vec2,
-//     pub bevel: f32,
-// }
+/// Rectangle of size `size` with corners rounded with a radius of `bevel`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+pub struct RoundedRectangle {
+    pub size: Vec2,
+    pub bevel: f32,
+}
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Triangle {
     pub a: Vec2,
     pub b: Vec2,
     pub c: Vec2,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Polygon {
     pub points: Vec<Vec2>,
     pub lines: Vec<[usize; 2]>,
     pub tris: Vec<[usize; 3]>,
 }
 
-// TODO: This exist only for `Pyramid` and `Extruded`. How would I go about
-// making them generic over which shape specifically we accept as base?
-// * Create a `Shape2dBase` trait that returns a `Polygon`, store `base: Polygon`
-//   in Pyramid, have helper method to build a Pyramid directly from a base
-//   shape using that trait.
-//   => This wouldn't work with Disc => Joly good, that's why we have Cylinder
-//   => Wouldn't work with RoundedRectangle too
-//   => Maybe have the `density` parameter to `Shape2dBase`?
-// * Make Pyramid generic over the base shape, Only accept base shape that
-//   implement a 2dShape trait of sort, worry about concrete implementations
-//   only when relevant, bounding over T impl for Pyramid<T>
+/// Any of the 2d shapes of this crate.
+///
+/// This is the default base of [`Pyramid`] and [`Extruded`].
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Shape2d {
     Disc(Disc),
     HalfDisc(HalfDisc),
     QuarterDisc(QuarterDisc),
+    Annulus(Annulus),
+    Ellipse(Ellipse),
     Rectangle(Rectangle),
-    // RoundedRectangle(RoundedRectangle),
+    RoundedRectangle(RoundedRectangle),
     Triangle(Triangle),
     Polygon(Polygon),
 }
 
 /// A Pyramid with a base of arbitrary shape.
 ///
-/// Note that [`Shape::Cylinder`] is a special case of this, where the
+/// The base can be any [`Shape2dBase`], it lies in the XZ plane.
+///
+/// Note that [`Cone`] is a special case of this, where the
 /// number of edges is minimized.
 #[derive(Debug, Clone)]
-pub struct Pyramid {
-    pub base: Shape2d,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pyramid<B = Shape2d> {
+    pub base: B,
     pub height: f32,
 }
 /// A 2d shape extruded on its perpendicular axis, to form a 3d shape.
 ///
-/// Note: [`Shape::Cuboid`] and [`Shape::Cylinder`] are special cases of a
+/// The base can be any [`Shape2dBase`], it lies in the XZ plane.
+///
+/// Note: [`Cuboid`] and [`Cylinder`] are special cases of a
 /// shape extrusion.
 #[derive(Debug, Clone)]
-pub struct Extruded {
-    pub base: Shape2d,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extruded<B = Shape2d> {
+    pub base: B,
     pub height: f32,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Sphere {
     pub radius: f32,
 }
 /// Upper half of a sphere.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct HalfSphere {
     pub radius: f32,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Capsule {
     pub radius: f32,
     pub segment_height: f32,
 }
 /// 3d rectangle specified as the full length of each edge.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Cuboid {
     pub size: Vec3,
 }
-// /// Cuboid of size `size` with bevelled edge.
-// #[derive(Debug, Clone)]
-// pub struct RoundedCuboid {
-//     pub size: Vec3,
-//     pub bevel: f32,
-// }
+/// Cuboid of size `size` with edges and corners rounded with a radius of
+/// `bevel`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+pub struct RoundedCuboid {
+    pub size: Vec3,
+    pub bevel: f32,
+}
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Cone {
     pub height: f32,
     pub base_radius: f32,
 }
 /// A four-cornered 3d shape.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Tetrahedron {
     pub a: Vec3,
     pub b: Vec3,
@@ -117,6 +184,7 @@ pub struct Tetrahedron {
 }
 /// A Collection of 3d lines.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lines {
     /// Points in 3d space.
     pub points: Vec<Vec3>,
@@ -124,12 +192,34 @@ pub struct Lines {
     pub lines: Vec<[usize; 2]>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct Cylinder {
     pub height: f32,
     pub radius: f32,
 }
+/// A ring around the Y axis.
+///
+/// `major_radius` is the distance from the Y axis to the center of the tube,
+/// `minor_radius` is the radius of the tube.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+pub struct Torus {
+    pub major_radius: f32,
+    pub minor_radius: f32,
+}
+/// Sphere stretched along the X, Y and Z axes by `half_axes`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
+pub struct Ellipsoid {
+    pub half_axes: Vec3,
+}
 /// 3d plane with varying height based on a grid.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect, FromReflect))]
 pub struct HeightField {
     pub heights: Vec<Vec<f32>>,
     pub size: Vec2,
@@ -0,0 +1,204 @@
+//! Volume and surface area of 3d shapes.
+//!
+//! The area and perimeter of 2d shapes are methods of [`Shape2dBase`].
+
+use std::f32::consts::{PI, TAU};
+
+use bevy_math::{Vec2, Vec3};
+
+use crate::*;
+
+/// The `density` used to approximate curved bases when computing the side
+/// surface of a [`Pyramid`].
+const MEASURE_DENSITY: usize = 32;
+
+pub(crate) fn triangle_area([a, b, c]: [Vec3; 3]) -> f32 {
+    (b - a).cross(c - a).length() / 2.0
+}
+
+impl<B: Shape2dBase> Pyramid<B> {
+    pub fn volume(&self) -> f32 {
+        self.base.area() * self.height / 3.0
+    }
+    /// Area of the base and the sides.
+    ///
+    /// When the base is curved, the sides are approximated.
+    pub fn surface_area(&self) -> f32 {
+        let apex = Vec3::Y * self.height;
+        let y3d = |v: Vec2| Vec3::new(v.x, 0.0, v.y);
+        let boundary = self.base.boundary(MEASURE_DENSITY);
+        let segments = boundary.iter().flat_map(|strip| strip.windows(2));
+        let sides = segments.map(|w| triangle_area([y3d(w[0]), y3d(w[1]), apex]));
+        self.base.area() + sides.sum::<f32>()
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    pub fn volume(&self) -> f32 {
+        self.base.area() * self.height
+    }
+    pub fn surface_area(&self) -> f32 {
+        2.0 * self.base.area() + self.base.perimeter() * self.height
+    }
+}
+impl Sphere {
+    pub fn volume(&self) -> f32 {
+        4.0 / 3.0 * PI * self.radius.powi(3)
+    }
+    pub fn surface_area(&self) -> f32 {
+        4.0 * PI * self.radius.powi(2)
+    }
+}
+impl HalfSphere {
+    pub fn volume(&self) -> f32 {
+        2.0 / 3.0 * PI * self.radius.powi(3)
+    }
+    /// Area of the dome and the flat disc.
+    pub fn surface_area(&self) -> f32 {
+        3.0 * PI * self.radius.powi(2)
+    }
+}
+impl Capsule {
+    pub fn volume(&self) -> f32 {
+        let Capsule { radius, segment_height } = *self;
+        PI * radius.powi(2) * (segment_height + 4.0 / 3.0 * radius)
+    }
+    pub fn surface_area(&self) -> f32 {
+        let Capsule { radius, segment_height } = *self;
+        2.0 * PI * radius * (segment_height + 2.0 * radius)
+    }
+}
+impl Cuboid {
+    pub fn volume(&self) -> f32 {
+        self.size.x * self.size.y * self.size.z
+    }
+    pub fn surface_area(&self) -> f32 {
+        let [x, y, z] = self.size.to_array();
+        2.0 * (x * y + y * z + z * x)
+    }
+}
+impl RoundedCuboid {
+    pub fn volume(&self) -> f32 {
+        let bevel = self.bevel;
+        let [x, y, z] = (self.size - 2.0 * bevel).to_array();
+        let faces = x * y + y * z + z * x;
+        let sphere = 4.0 / 3.0 * PI * bevel.powi(3);
+        x * y * z + 2.0 * bevel * faces + PI * bevel * bevel * (x + y + z) + sphere
+    }
+    pub fn surface_area(&self) -> f32 {
+        let bevel = self.bevel;
+        let [x, y, z] = (self.size - 2.0 * bevel).to_array();
+        let faces = x * y + y * z + z * x;
+        2.0 * faces + TAU * bevel * (x + y + z) + 4.0 * PI * bevel * bevel
+    }
+}
+impl Cone {
+    pub fn volume(&self) -> f32 {
+        PI * self.base_radius.powi(2) * self.height / 3.0
+    }
+    pub fn surface_area(&self) -> f32 {
+        let Cone { height, base_radius: r } = *self;
+        PI * r * (r + (r * r + height * height).sqrt())
+    }
+}
+impl Tetrahedron {
+    pub fn volume(&self) -> f32 {
+        let Tetrahedron { a, b, c, d } = *self;
+        (b - a).dot((c - a).cross(d - a)).abs() / 6.0
+    }
+    pub fn surface_area(&self) -> f32 {
+        let Tetrahedron { a, b, c, d } = *self;
+        let faces = [[a, b, c], [a, b, d], [a, c, d], [b, c, d]];
+        faces.into_iter().map(triangle_area).sum()
+    }
+}
+impl Lines {
+    /// The total length of `lines`.
+    pub fn length(&self) -> f32 {
+        let points = &self.points;
+        let length = |[a, b]: &[usize; 2]| points[*a].distance(points[*b]);
+        self.lines.iter().map(length).sum()
+    }
+}
+impl Cylinder {
+    pub fn volume(&self) -> f32 {
+        PI * self.radius.powi(2) * self.height
+    }
+    pub fn surface_area(&self) -> f32 {
+        2.0 * PI * self.radius * (self.radius + self.height)
+    }
+}
+impl Torus {
+    pub fn volume(&self) -> f32 {
+        2.0 * PI * PI * self.major_radius * self.minor_radius.powi(2)
+    }
+    pub fn surface_area(&self) -> f32 {
+        4.0 * PI * PI * self.major_radius * self.minor_radius
+    }
+}
+impl Ellipsoid {
+    pub fn volume(&self) -> f32 {
+        4.0 / 3.0 * PI * self.half_axes.x * self.half_axes.y * self.half_axes.z
+    }
+    /// There is no closed form for the area of an ellipsoid, this uses
+    /// Knud Thomsen's approximation, within 1.1% of the actual area.
+    pub fn surface_area(&self) -> f32 {
+        const P: f32 = 1.6075;
+        let [x, y, z] = self.half_axes.powf(P).to_array();
+        4.0 * PI * ((x * y + x * z + y * z) / 3.0).powf(1.0 / P)
+    }
+}
+impl HeightField {
+    /// How many cells there are along the X and Z axes.
+    pub(crate) fn cell_count(&self) -> (usize, usize) {
+        (self.heights.len() - 1, self.heights[0].len() - 1)
+    }
+    /// The two triangles of the cell between `heights[x][y]` and
+    /// `heights[x + 1][y + 1]`.
+    ///
+    /// The diagonal splitting the cell alternates with `x`, like the zig-zag
+    /// of the outline and the fill of `bevy_cool_shapes_render`.
+    pub(crate) fn cell(&self, x: usize, y: usize) -> [[Vec3; 3]; 2] {
+        let (x_cells, y_cells) = self.cell_count();
+        let size = self.size;
+        let x_coord = |x| size.x / (x_cells as f32) * (x as f32) - size.x / 2.0;
+        let y_coord = |y| size.y / (y_cells as f32) * (y as f32) - size.y / 2.0;
+        let point_at = |x: usize, y: usize| Vec3::new(x_coord(x), self.heights[x][y], y_coord(y));
+        let (a, b) = (point_at(x, y), point_at(x + 1, y));
+        let (c, d) = (point_at(x, y + 1), point_at(x + 1, y + 1));
+        if x % 2 == 1 {
+            [[a, b, d], [a, d, c]]
+        } else {
+            [[a, b, c], [c, b, d]]
+        }
+    }
+    /// Calls `f` with the two triangles of each cell.
+    pub(crate) fn for_each_cell(&self, mut f: impl FnMut([Vec3; 3], [Vec3; 3])) {
+        let (x_cells, y_cells) = self.cell_count();
+        for x in 0..x_cells {
+            for y in 0..y_cells {
+                let [left, right] = self.cell(x, y);
+                f(left, right);
+            }
+        }
+    }
+    /// Volume between the surface and the XZ plane.
+    ///
+    /// Parts of the surface below the plane count negatively.
+    pub fn volume(&self) -> f32 {
+        let (x_cells, y_cells) = self.cell_count();
+        let cell_area = self.size.x * self.size.y / (x_cells * y_cells) as f32;
+        let mut volume = 0.0;
+        let mean_height = |[a, b, c]: [Vec3; 3]| (a.y + b.y + c.y) / 3.0;
+        self.for_each_cell(|left, right| {
+            // Both triangles cover half of the cell.
+            volume += (mean_height(left) + mean_height(right)) / 2.0 * cell_area;
+        });
+        volume
+    }
+    /// Area of the surface, not including the sides.
+    pub fn surface_area(&self) -> f32 {
+        let mut area = 0.0;
+        self.for_each_cell(|left, right| area += triangle_area(left) + triangle_area(right));
+        area
+    }
+}
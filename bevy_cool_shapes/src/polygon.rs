@@ -0,0 +1,165 @@
+//! Building [`Polygon`]s from their boundary.
+
+use bevy_math::Vec2;
+
+use crate::Polygon;
+
+impl Polygon {
+    /// A polygon delimited by `boundary`, with `lines` and `tris` filled.
+    ///
+    /// `boundary` can be in either winding order, it is closed
+    /// automatically, repeating the first point at the end is not needed.
+    pub fn new(boundary: impl IntoIterator<Item = Vec2>) -> Self {
+        Self::with_holes(boundary, Vec::<Vec<Vec2>>::new())
+    }
+    /// A polygon delimited by `boundary`, with `holes` cut out of it.
+    ///
+    /// Holes must be inside `boundary` and not overlap each other, holes
+    /// without area are ignored.
+    pub fn with_holes<H: IntoIterator<Item = Vec2>>(
+        boundary: impl IntoIterator<Item = Vec2>,
+        holes: impl IntoIterator<Item = H>,
+    ) -> Self {
+        let mut points = Vec::new();
+        let mut lines = Vec::new();
+        let mut add_loop = |loop_: Vec<Vec2>, counter_clockwise: bool| {
+            let start = points.len();
+            let mut indices: Vec<_> = (start..start + loop_.len()).collect();
+            if (signed_area(&loop_) > 0.0) != counter_clockwise {
+                indices.reverse();
+            }
+            points.extend(loop_);
+            let next = indices.iter().skip(1).chain(indices.first());
+            lines.extend(indices.iter().zip(next).map(|(a, b)| [*a, *b]));
+            indices
+        };
+        let outer = add_loop(open_loop(boundary), true);
+        // Holes with less than 3 distinct points or all on a line have no
+        // area, they would only add a bridge to nowhere.
+        let holes: Vec<_> = holes
+            .into_iter()
+            .map(open_loop)
+            .filter(|hole| signed_area(hole) != 0.0)
+            .map(|hole| add_loop(hole, false))
+            .collect();
+        let merged = merge_holes(&points, outer, holes);
+        let tris = ear_clip(&points, merged);
+        Polygon { points, lines, tris }
+    }
+}
+
+/// The points of `loop_`, without the last point if it repeats the first.
+fn open_loop(loop_: impl IntoIterator<Item = Vec2>) -> Vec<Vec2> {
+    let mut loop_: Vec<_> = loop_.into_iter().collect();
+    if loop_.len() > 1 && loop_.first() == loop_.last() {
+        loop_.pop();
+    }
+    loop_
+}
+/// Positive if `loop_` is counter-clockwise.
+fn signed_area(loop_: &[Vec2]) -> f32 {
+    let next = loop_.iter().skip(1).chain(loop_.first());
+    loop_
+        .iter()
+        .zip(next)
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum::<f32>()
+        / 2.0
+}
+/// Positive if `a`, `b`, `c` turn counter-clockwise.
+fn turn(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+/// Whether the `[a, b]` and `[c, d]` segments cross, not counting touching
+/// ends.
+fn crosses([a, b]: [Vec2; 2], [c, d]: [Vec2; 2]) -> bool {
+    let opposite = |x: f32, y: f32| (x > 0.0 && y < 0.0) || (x < 0.0 && y > 0.0);
+    opposite(turn(a, b, c), turn(a, b, d)) && opposite(turn(c, d, a), turn(c, d, b))
+}
+
+/// Connect each of `holes` to `outer` with a bridge going back and forth,
+/// making a single loop of indices into `points`.
+///
+/// `outer` must be counter-clockwise and `holes` clockwise.
+fn merge_holes(points: &[Vec2], outer: Vec<usize>, mut holes: Vec<Vec<usize>>) -> Vec<usize> {
+    let edges = |loop_: &[usize]| {
+        let next = loop_.iter().skip(1).chain(loop_.first());
+        let edges = loop_.iter().zip(next).map(|(a, b)| [*a, *b]);
+        edges.collect::<Vec<_>>()
+    };
+    // Start with the rightmost holes, so that bridges from holes on the left
+    // do not need to go around them.
+    let rightmost = |hole: &[usize]| {
+        let by_x = |a: &&usize, b: &&usize| points[**a].x.total_cmp(&points[**b].x);
+        *hole.iter().max_by(by_x).unwrap()
+    };
+    holes.sort_by(|a, b| points[rightmost(b)].x.total_cmp(&points[rightmost(a)].x));
+
+    let mut merged = outer;
+    for (i, hole) in holes.iter().enumerate() {
+        let from = rightmost(hole);
+        let mut blockers = edges(&merged);
+        blockers.extend(holes[i..].iter().flat_map(|hole| edges(hole)));
+        let visible = |to: usize| {
+            let bridge = [points[from], points[to]];
+            let touches = |[a, b]: &[usize; 2]| [*a, *b].iter().any(|v| [from, to].contains(v));
+            let blocked = |edge: &[usize; 2]| {
+                !touches(edge) && crosses(bridge, [points[edge[0]], points[edge[1]]])
+            };
+            !blockers.iter().any(blocked)
+        };
+        let distance = |to: &usize| points[from].distance_squared(points[*to]);
+        let mut candidates: Vec<_> = (0..merged.len()).collect();
+        candidates.sort_by(|a, b| distance(&merged[*a]).total_cmp(&distance(&merged[*b])));
+        let bridge = match candidates.into_iter().find(|i| visible(merged[*i])) {
+            Some(bridge) => bridge,
+            None => continue,
+        };
+        // Go from the bridge vertex around the hole, and come back.
+        let start = hole.iter().position(|v| *v == from).unwrap();
+        let around = hole[start..].iter().chain(&hole[..=start]).copied();
+        let back = merged[bridge];
+        let insert: Vec<_> = around.chain([back]).collect();
+        merged.splice(bridge + 1..bridge + 1, insert);
+    }
+    merged
+}
+
+/// Triangulate the counter-clockwise `polygon` loop of indices into `points`
+/// by ear clipping.
+fn ear_clip(points: &[Vec2], mut polygon: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut tris = Vec::with_capacity(polygon.len().saturating_sub(2));
+    while polygon.len() >= 3 {
+        let len = polygon.len();
+        let corner = |i: usize| {
+            let [a, b, c] = [(i + len - 1) % len, i, (i + 1) % len].map(|i| polygon[i]);
+            ([a, b, c], turn(points[a], points[b], points[c]))
+        };
+        let is_ear = |i: usize| {
+            let (tri, turn_) = corner(i);
+            let [a, b, c] = tri.map(|v| points[v]);
+            let inside = |v: &usize| {
+                let p = points[*v];
+                let not_corner = !tri.contains(v) && ![a, b, c].contains(&p);
+                not_corner && turn(a, b, p) >= 0.0 && turn(b, c, p) >= 0.0 && turn(c, a, p) >= 0.0
+            };
+            turn_ > 0.0 && !polygon.iter().any(inside)
+        };
+        // Flat corners are removed without adding a triangle.
+        if let Some(flat) = (0..len).find(|i| corner(*i).1 == 0.0) {
+            polygon.remove(flat);
+            continue;
+        }
+        // If there is no ear, the polygon is not simple, clip any convex
+        // corner to make progress.
+        let ear = (0..len).find(|i| is_ear(*i));
+        let ear = ear.or_else(|| (0..len).find(|i| corner(*i).1 > 0.0));
+        let ear = match ear {
+            Some(ear) => ear,
+            None => break,
+        };
+        tris.push(corner(ear).0);
+        polygon.remove(ear);
+    }
+    tris
+}
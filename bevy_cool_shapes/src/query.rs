@@ -0,0 +1,455 @@
+//! Overlap and distance queries between convex shapes.
+//!
+//! Shapes implementing [`SupportMap`] can be tested against each other with
+//! [`contact`] and [`intersects`]. The distance is computed with the GJK
+//! algorithm, and the penetration of overlapping shapes with EPA.
+//!
+//! ```rust
+//! use bevy_cool_shapes::{query, Cuboid, Sphere};
+//! use bevy_math::{Mat4, Vec3};
+//!
+//! let sphere = Sphere { radius: 1.0 };
+//! let cuboid = Cuboid { size: Vec3::splat(2.0) };
+//! let far = Mat4::from_translation(Vec3::X * 3.0);
+//! let distance = query::contact(&sphere, Mat4::IDENTITY, &cuboid, far).distance();
+//! assert!((distance - 1.0).abs() < 1e-3);
+//! ```
+
+use bevy_math::{Mat3, Mat4, Vec3, Vec3Swizzles};
+
+use crate::sdf::{segment_barycentric, triangle_barycentric};
+use crate::*;
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f32 = 1e-6;
+
+/// A convex shape defined by its furthest point in any direction.
+pub trait SupportMap {
+    /// The point of the shape the furthest in `direction`.
+    fn support(&self, direction: Vec3) -> Vec3;
+}
+
+/// How two shapes are placed relative to each other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Contact {
+    /// The shapes do not overlap.
+    Separated {
+        distance: f32,
+        /// The point of `a` closest to `b`.
+        point_a: Vec3,
+        /// The point of `b` closest to `a`.
+        point_b: Vec3,
+    },
+    /// The shapes overlap.
+    Penetrating {
+        /// How far `b` needs to move along `normal` to stop overlapping `a`.
+        depth: f32,
+        /// Points from `a` toward `b`, zero if the shapes barely touch.
+        normal: Vec3,
+        /// The point of `a` the deepest inside `b`.
+        point_a: Vec3,
+        /// The point of `b` the deepest inside `a`.
+        point_b: Vec3,
+    },
+}
+impl Contact {
+    /// The distance between the shapes, minus the penetration depth if they
+    /// overlap.
+    pub fn distance(&self) -> f32 {
+        match self {
+            Contact::Separated { distance, .. } => *distance,
+            Contact::Penetrating { depth, .. } => -depth,
+        }
+    }
+    pub fn is_penetrating(&self) -> bool {
+        matches!(self, Contact::Penetrating { .. })
+    }
+}
+
+/// The distance or penetration between `a` and `b`, placed in the world
+/// with `a_transform` and `b_transform`.
+pub fn contact(
+    a: &impl SupportMap,
+    a_transform: Mat4,
+    b: &impl SupportMap,
+    b_transform: Mat4,
+) -> Contact {
+    let difference = Difference::new(a, a_transform, b, b_transform);
+    match gjk(&difference) {
+        Gjk::Separated(simplex) => {
+            let (point_a, point_b) = (simplex.point(|v| v.a), simplex.point(|v| v.b));
+            let distance = point_a.distance(point_b);
+            Contact::Separated { distance, point_a, point_b }
+        }
+        Gjk::Overlapping(vertices) => epa(&difference, vertices),
+    }
+}
+/// Whether `a` and `b` overlap, placed in the world with `a_transform` and
+/// `b_transform`.
+///
+/// This is cheaper than [`contact`].
+pub fn intersects(
+    a: &impl SupportMap,
+    a_transform: Mat4,
+    b: &impl SupportMap,
+    b_transform: Mat4,
+) -> bool {
+    let difference = Difference::new(a, a_transform, b, b_transform);
+    matches!(gjk(&difference), Gjk::Overlapping(_))
+}
+
+/// A point of the Minkowski difference `a - b`, with the points of `a` and
+/// `b` it comes from.
+#[derive(Clone, Copy)]
+struct Vertex {
+    a: Vec3,
+    b: Vec3,
+    w: Vec3,
+}
+/// A [`SupportMap`] placed in the world.
+struct Placed<'a> {
+    shape: &'a dyn SupportMap,
+    transform: Mat4,
+    /// Transforms world directions into the shape's local space.
+    to_local: Mat3,
+}
+impl<'a> Placed<'a> {
+    fn new(shape: &'a dyn SupportMap, transform: Mat4) -> Self {
+        let to_local = Mat3::from_mat4(transform).transpose();
+        Placed { shape, transform, to_local }
+    }
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let local = self.shape.support(self.to_local * direction);
+        self.transform.transform_point3(local)
+    }
+}
+/// The Minkowski difference `a - b`, containing the origin when `a` and `b`
+/// overlap.
+struct Difference<'a> {
+    a: Placed<'a>,
+    b: Placed<'a>,
+}
+impl<'a> Difference<'a> {
+    fn new(a: &'a dyn SupportMap, a_at: Mat4, b: &'a dyn SupportMap, b_at: Mat4) -> Self {
+        Difference { a: Placed::new(a, a_at), b: Placed::new(b, b_at) }
+    }
+    fn support(&self, direction: Vec3) -> Vertex {
+        let (a, b) = (self.a.support(direction), self.b.support(-direction));
+        Vertex { a, b, w: a - b }
+    }
+}
+
+/// Up to four vertices, with the barycentric coordinates of their point
+/// closest to the origin.
+struct Simplex {
+    vertices: Vec<Vertex>,
+    weights: Vec<f32>,
+}
+impl Simplex {
+    fn point(&self, f: impl Fn(&Vertex) -> Vec3) -> Vec3 {
+        let points = self.vertices.iter().map(f);
+        weighted_sum(points.zip(self.weights.iter().copied()))
+    }
+    /// Update `weights` and drop the vertices not needed to reach the point
+    /// closest to the origin.
+    fn reduce(&mut self) {
+        let w: Vec<_> = self.vertices.iter().map(|v| v.w).collect();
+        self.weights = match *w.as_slice() {
+            [_] => vec![1.0],
+            [a, b] => segment_barycentric(Vec3::ZERO, [a, b]).to_vec(),
+            [a, b, c] => triangle_barycentric(Vec3::ZERO, [a, b, c]).to_vec(),
+            [a, b, c, d] => tetrahedron_barycentric([a, b, c, d]),
+            _ => unreachable!("a simplex has between 1 and 4 vertices"),
+        };
+        let mut weights = self.weights.iter();
+        self.vertices.retain(|_| *weights.next().unwrap() > 0.0);
+        self.weights.retain(|weight| *weight > 0.0);
+    }
+}
+/// The barycentric coordinates of the point of the `[a, b, c, d]`
+/// tetrahedron closest to the origin.
+fn tetrahedron_barycentric([a, b, c, d]: [Vec3; 4]) -> Vec<f32> {
+    let volume = |a: Vec3, b: Vec3, c: Vec3, d: Vec3| (b - a).dot((c - a).cross(d - a));
+    let total = volume(a, b, c, d);
+    let o = Vec3::ZERO;
+    let inside = [
+        volume(o, b, c, d),
+        volume(a, o, c, d),
+        volume(a, b, o, d),
+        volume(a, b, c, o),
+    ];
+    let inside = inside.map(|v| v / total);
+    if total.abs() > TOLERANCE && inside.iter().all(|v| *v >= 0.0) {
+        return inside.to_vec();
+    }
+    // The closest point is on one of the faces.
+    let faces = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+    let vertices = [a, b, c, d];
+    let on_face = |face: [usize; 3]| {
+        let weights = triangle_barycentric(Vec3::ZERO, face.map(|i| vertices[i]));
+        let mut all = vec![0.0; 4];
+        face.iter()
+            .zip(weights)
+            .for_each(|(i, weight)| all[*i] = weight);
+        let point = weighted_sum(vertices.into_iter().zip(all.iter().copied()));
+        (point.length_squared(), all)
+    };
+    let closest = |a: (f32, Vec<f32>), b: (f32, Vec<f32>)| if a.0 <= b.0 { a } else { b };
+    faces.into_iter().map(on_face).reduce(closest).unwrap().1
+}
+
+enum Gjk {
+    Separated(Simplex),
+    /// The last simplex, touching or containing the origin.
+    Overlapping(Vec<Vertex>),
+}
+fn gjk(difference: &Difference) -> Gjk {
+    let first = difference.support(Vec3::X);
+    let mut simplex = Simplex { vertices: vec![first], weights: vec![1.0] };
+    for _ in 0..MAX_ITERATIONS {
+        simplex.reduce();
+        let closest = simplex.point(|v| v.w);
+        let distance_squared = closest.length_squared();
+        if distance_squared <= TOLERANCE || simplex.vertices.len() == 4 {
+            return Gjk::Overlapping(simplex.vertices);
+        }
+        let new = difference.support(-closest);
+        let progress = distance_squared - closest.dot(new.w);
+        let known = simplex.vertices.iter().any(|v| v.w == new.w);
+        if progress <= TOLERANCE * distance_squared || known {
+            return Gjk::Separated(simplex);
+        }
+        simplex.vertices.push(new);
+    }
+    Gjk::Separated(simplex)
+}
+
+/// Add vertices to `simplex` until it is a tetrahedron, returns `false` if
+/// the Minkowski difference is flat.
+fn fill_simplex(difference: &Difference, simplex: &mut Vec<Vertex>) -> bool {
+    let axes = [Vec3::X, Vec3::Y, Vec3::Z];
+    let directions = axes.into_iter().flat_map(|axis| [axis, -axis]);
+    let push_first = |simplex: &mut Vec<Vertex>,
+                      directions: &mut dyn Iterator<Item = Vec3>,
+                      valid: &dyn Fn(Vec3) -> bool| {
+        let mut candidates = directions.map(|d| difference.support(d));
+        match candidates.find(|v| valid(v.w)) {
+            Some(vertex) => simplex.push(vertex),
+            None => return false,
+        }
+        true
+    };
+    if simplex.len() == 1 {
+        let first = simplex[0].w;
+        let distinct = |w: Vec3| w.distance_squared(first) > TOLERANCE;
+        if !push_first(simplex, &mut directions.clone(), &distinct) {
+            return false;
+        }
+    }
+    if simplex.len() == 2 {
+        let (first, line) = (simplex[0].w, simplex[1].w - simplex[0].w);
+        let mut normals = directions.map(|axis| line.cross(axis));
+        let off_line = |w: Vec3| (w - first).cross(line).length_squared() > TOLERANCE;
+        if !push_first(simplex, &mut normals, &off_line) {
+            return false;
+        }
+    }
+    if simplex.len() == 3 {
+        let first = simplex[0].w;
+        let normal = (simplex[1].w - first).cross(simplex[2].w - first);
+        let off_plane = |w: Vec3| (w - first).dot(normal).abs() > TOLERANCE;
+        if !push_first(simplex, &mut [normal, -normal].into_iter(), &off_plane) {
+            return false;
+        }
+    }
+    true
+}
+
+struct Face {
+    vertices: [usize; 3],
+    /// Pointing out of the polytope.
+    normal: Vec3,
+    /// Distance from the origin to the plane of the face.
+    distance: f32,
+}
+impl Face {
+    /// The face, flipped if needed so that it faces away from `inside`.
+    fn new(points: &[Vertex], [a, b, c]: [usize; 3], inside: Vec3) -> Self {
+        let (pa, pb, pc) = (points[a].w, points[b].w, points[c].w);
+        let normal = (pb - pa).cross(pc - pa).normalize_or_zero();
+        let (vertices, normal) = if normal.dot(inside - pa) > 0.0 {
+            ([a, c, b], -normal)
+        } else {
+            ([a, b, c], normal)
+        };
+        let distance = if normal == Vec3::ZERO {
+            f32::INFINITY
+        } else {
+            normal.dot(pa)
+        };
+        Face { vertices, normal, distance }
+    }
+}
+/// Expand the polytope `simplex` until reaching the boundary of the
+/// Minkowski difference closest to the origin.
+fn epa(difference: &Difference, mut points: Vec<Vertex>) -> Contact {
+    if !fill_simplex(difference, &mut points) {
+        let vertex = points[0];
+        let (point_a, point_b) = (vertex.a, vertex.b);
+        return Contact::Penetrating { depth: 0.0, normal: Vec3::ZERO, point_a, point_b };
+    }
+    let inside = weighted_sum(points.iter().map(|v| (v.w, 0.25)));
+    let tetrahedron = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+    let mut faces: Vec<_> = tetrahedron
+        .into_iter()
+        .map(|face| Face::new(&points, face, inside))
+        .collect();
+    for _ in 0..MAX_ITERATIONS {
+        let closest = closest_face(&faces);
+        let Face { normal, distance, .. } = faces[closest];
+        let new = difference.support(normal);
+        if new.w.dot(normal) - distance <= TOLERANCE.sqrt() {
+            break;
+        }
+        let new_index = points.len();
+        points.push(new);
+        // Replace all faces seen from `new` by faces connecting their
+        // outline to `new`.
+        let mut horizon: Vec<[usize; 2]> = Vec::new();
+        faces.retain(|face| {
+            let visible = face.normal.dot(new.w - points[face.vertices[0]].w) > 0.0;
+            if visible {
+                let [a, b, c] = face.vertices;
+                for [start, end] in [[a, b], [b, c], [c, a]] {
+                    match horizon.iter().position(|edge| *edge == [end, start]) {
+                        Some(shared) => drop(horizon.swap_remove(shared)),
+                        None => horizon.push([start, end]),
+                    }
+                }
+            }
+            !visible
+        });
+        let new_faces = horizon
+            .into_iter()
+            .map(|[start, end]| Face::new(&points, [start, end, new_index], inside));
+        faces.extend(new_faces);
+    }
+    let face = &faces[closest_face(&faces)];
+    let corners = face.vertices.map(|i| points[i]);
+    let on_face = face.normal * face.distance;
+    let weights = triangle_barycentric(on_face, corners.map(|v| v.w));
+    let point = |f: fn(&Vertex) -> Vec3| weighted_sum(corners.iter().map(f).zip(weights));
+    Contact::Penetrating {
+        depth: face.distance.max(0.0),
+        normal: face.normal,
+        point_a: point(|v| v.a),
+        point_b: point(|v| v.b),
+    }
+}
+fn closest_face(faces: &[Face]) -> usize {
+    let distances = faces.iter().map(|face| face.distance).enumerate();
+    let closest = |a: (usize, f32), b: (usize, f32)| if a.1 <= b.1 { a } else { b };
+    distances.reduce(closest).map_or(0, |(i, _)| i)
+}
+
+fn weighted_sum(points: impl IntoIterator<Item = (Vec3, f32)>) -> Vec3 {
+    let add = |sum: Vec3, (point, weight): (Vec3, f32)| sum + point * weight;
+    points.into_iter().fold(Vec3::ZERO, add)
+}
+/// The point of `points` the furthest in `direction`.
+fn max_dot(points: impl IntoIterator<Item = Vec3>, direction: Vec3) -> Vec3 {
+    let furthest = |a: Vec3, b: Vec3| if a.dot(direction) >= b.dot(direction) { a } else { b };
+    points.into_iter().reduce(furthest).unwrap_or(Vec3::ZERO)
+}
+fn sign(v: f32) -> f32 {
+    if v >= 0.0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+/// `direction` projected on the XZ plane, normalized.
+fn horizontal(direction: Vec3) -> Vec3 {
+    Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero()
+}
+
+/// Non-convex bases are treated as their convex hull.
+impl<B: Shape2dBase> SupportMap for Pyramid<B> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let half_height = self.height / 2.0;
+        let base = self.base.support(direction.xz());
+        let base = Vec3::new(base.x, -half_height, base.y);
+        max_dot([base, Vec3::Y * half_height], direction)
+    }
+}
+/// Non-convex bases are treated as their convex hull.
+impl<B: Shape2dBase> SupportMap for Extruded<B> {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let base = self.base.support(direction.xz());
+        Vec3::new(base.x, sign(direction.y) * self.height / 2.0, base.y)
+    }
+}
+impl SupportMap for Sphere {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        direction.normalize_or_zero() * self.radius
+    }
+}
+impl SupportMap for HalfSphere {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        if direction.y > 0.0 {
+            direction.normalize() * self.radius
+        } else {
+            horizontal(direction) * self.radius
+        }
+    }
+}
+impl SupportMap for Capsule {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let end = Vec3::Y * sign(direction.y) * self.segment_height / 2.0;
+        end + direction.normalize_or_zero() * self.radius
+    }
+}
+impl SupportMap for Cuboid {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let signs = Vec3::new(sign(direction.x), sign(direction.y), sign(direction.z));
+        self.size / 2.0 * signs
+    }
+}
+impl SupportMap for RoundedCuboid {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let signs = Vec3::new(sign(direction.x), sign(direction.y), sign(direction.z));
+        let corner = (self.size / 2.0 - self.bevel) * signs;
+        corner + direction.normalize_or_zero() * self.bevel
+    }
+}
+impl SupportMap for Cone {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let half_height = Vec3::Y * self.height / 2.0;
+        let rim = horizontal(direction) * self.base_radius - half_height;
+        max_dot([rim, half_height], direction)
+    }
+}
+impl SupportMap for Tetrahedron {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let Tetrahedron { a, b, c, d } = *self;
+        max_dot([a, b, c, d], direction)
+    }
+}
+impl SupportMap for Cylinder {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let end = Vec3::Y * sign(direction.y) * self.height / 2.0;
+        end + horizontal(direction) * self.radius
+    }
+}
+impl SupportMap for Ellipsoid {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        self.half_axes * (self.half_axes * direction).normalize_or_zero()
+    }
+}
+/// The torus is treated as its convex hull.
+impl SupportMap for Torus {
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let center = horizontal(direction) * self.major_radius;
+        center + direction.normalize_or_zero() * self.minor_radius
+    }
+}
@@ -0,0 +1,510 @@
+//! Ray casting against shapes.
+//!
+//! Rays are defined by an `origin`, a direction `dir` and a `max_toi`, the
+//! ray only hits points `origin + dir * toi` where `toi` is between `0.0` and
+//! `max_toi`. Rays starting inside a shape hit it at their origin, with a
+//! zero normal. 2d shapes implement it with [`Shape2dBase::cast_ray`].
+
+use bevy_math::{Vec2, Vec3, Vec3Swizzles};
+
+use crate::*;
+
+/// The `density` used to approximate curved bases of [`Pyramid`] and
+/// [`Extruded`].
+const RAY_DENSITY: usize = 32;
+/// How many steps are taken when sphere tracing a [`Torus`].
+const TRACE_STEPS: usize = 128;
+/// How close to the surface of a [`Torus`] sphere tracing stops, relative to
+/// its size.
+const TRACE_TOLERANCE: f32 = 1e-5;
+
+/// Where a ray hits a 3d shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// The hit point is at `origin + dir * toi`.
+    pub toi: f32,
+    pub point: Vec3,
+    /// The normal of the surface at `point`, facing the ray origin.
+    pub normal: Vec3,
+}
+/// Where a ray hits a 2d shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit2d {
+    /// The hit point is at `origin + dir * toi`.
+    pub toi: f32,
+    pub point: Vec2,
+    /// The normal of the boundary at `point`, facing the ray origin.
+    pub normal: Vec2,
+}
+
+/// The `toi`s at which a ray hits the `a * toi² + b * toi + c = 0` surface,
+/// in increasing order.
+fn quadratic_roots(a: f32, b: f32, c: f32) -> Option<[f32; 2]> {
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 || a == 0.0 {
+        return None;
+    }
+    let sqrt = discriminant.sqrt();
+    let (near, far) = ((-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a));
+    Some(if near <= far { [near, far] } else { [far, near] })
+}
+
+/// The closest `(toi, normal)` of `hits` within `0.0..=max_toi`.
+fn nearest(hits: impl IntoIterator<Item = (f32, Vec3)>, max_toi: f32) -> Option<(f32, Vec3)> {
+    let in_range = |(toi, _): &(f32, Vec3)| (0.0..=max_toi).contains(toi);
+    let closest = |a: (f32, Vec3), b: (f32, Vec3)| if a.0 <= b.0 { a } else { b };
+    hits.into_iter().filter(in_range).reduce(closest)
+}
+fn ray_hit(origin: Vec3, dir: Vec3, (toi, normal): (f32, Vec3)) -> RayHit {
+    RayHit { toi, point: origin + dir * toi, normal }
+}
+fn inside_hit(origin: Vec3) -> Option<RayHit> {
+    Some(RayHit { toi: 0.0, point: origin, normal: Vec3::ZERO })
+}
+/// Flip `normal` so that it faces against `dir`.
+fn facing(normal: Vec3, dir: Vec3) -> Vec3 {
+    if normal.dot(dir) > 0.0 {
+        -normal
+    } else {
+        normal
+    }
+}
+
+/// Hits of a ray with the sphere of `radius` around `center`.
+fn sphere_hits(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Vec<(f32, Vec3)> {
+    let o = origin - center;
+    let roots = quadratic_roots(dir.dot(dir), 2.0 * o.dot(dir), o.dot(o) - radius * radius);
+    let normal = |toi| (o + dir * toi) / radius;
+    roots
+        .into_iter()
+        .flatten()
+        .map(|toi| (toi, normal(toi)))
+        .collect()
+}
+/// Hits of a ray with the infinite cylinder of `radius` around the Y axis.
+fn cylinder_hits(origin: Vec3, dir: Vec3, radius: f32) -> Vec<(f32, Vec3)> {
+    let (o, d) = (origin.xz(), dir.xz());
+    let roots = quadratic_roots(d.dot(d), 2.0 * o.dot(d), o.dot(o) - radius * radius);
+    let normal = |toi| {
+        let p: Vec2 = (o + d * toi) / radius;
+        Vec3::new(p.x, 0.0, p.y)
+    };
+    roots
+        .into_iter()
+        .flatten()
+        .map(|toi| (toi, normal(toi)))
+        .collect()
+}
+/// The `toi` at which a ray crosses the horizontal plane at height `y`.
+fn plane_y_hit(origin: Vec3, dir: Vec3, y: f32) -> Option<f32> {
+    let toi = (y - origin.y) / dir.y;
+    toi.is_finite().then_some(toi)
+}
+/// The `toi` at which a ray hits the `[a, b, c]` triangle, from either side.
+///
+/// Möller–Trumbore intersection algorithm.
+pub(crate) fn triangle_hit(origin: Vec3, dir: Vec3, [a, b, c]: [Vec3; 3]) -> Option<(f32, Vec3)> {
+    let (ab, ac) = (b - a, c - a);
+    let p = dir.cross(ac);
+    let det = ab.dot(p);
+    // `det` scales with the edges and `dir`, so that small triangles are not
+    // mistaken for rays parallel to them.
+    let scale = ab.length() * ac.length() * dir.length();
+    if det.abs() <= f32::EPSILON * scale {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let ao = origin - a;
+    let u = ao.dot(p) * inv_det;
+    let q = ao.cross(ab);
+    let v = dir.dot(q) * inv_det;
+    if u < 0.0 || v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let toi = ac.dot(q) * inv_det;
+    Some((toi, facing(ab.cross(ac).normalize(), dir)))
+}
+
+/// The hit of a ray with the `[start, end]` segment.
+pub(crate) fn segment_hit_2d(
+    origin: Vec2,
+    dir: Vec2,
+    [start, end]: [Vec2; 2],
+) -> Option<(f32, Vec2)> {
+    let segment = end - start;
+    let denom = dir.perp_dot(segment);
+    if denom == 0.0 {
+        return None;
+    }
+    let to_start = start - origin;
+    let toi = to_start.perp_dot(segment) / denom;
+    let along = to_start.perp_dot(dir) / denom;
+    if !(0.0..=1.0).contains(&along) {
+        return None;
+    }
+    let normal = segment.perp().normalize();
+    let normal = if normal.dot(dir) > 0.0 { -normal } else { normal };
+    Some((toi, normal))
+}
+/// Hits of a ray with the circle of `radius` around the origin.
+pub(crate) fn circle_hits_2d(origin: Vec2, dir: Vec2, radius: f32) -> Vec<(f32, Vec2)> {
+    let roots = quadratic_roots(
+        dir.dot(dir),
+        2.0 * origin.dot(dir),
+        origin.dot(origin) - radius * radius,
+    );
+    let normal = |toi| (origin + dir * toi) / radius;
+    roots
+        .into_iter()
+        .flatten()
+        .map(|toi| (toi, normal(toi)))
+        .collect()
+}
+/// The closest of `hits` within `0.0..=max_toi`, as a [`RayHit2d`].
+pub(crate) fn nearest_2d(
+    origin: Vec2,
+    dir: Vec2,
+    max_toi: f32,
+    hits: impl IntoIterator<Item = (f32, Vec2)>,
+) -> Option<RayHit2d> {
+    let in_range = |(toi, _): &(f32, Vec2)| (0.0..=max_toi).contains(toi);
+    let closest = |a: (f32, Vec2), b: (f32, Vec2)| if a.0 <= b.0 { a } else { b };
+    let (toi, normal) = hits.into_iter().filter(in_range).reduce(closest)?;
+    Some(RayHit2d { toi, point: origin + dir * toi, normal })
+}
+pub(crate) fn inside_hit_2d(origin: Vec2) -> Option<RayHit2d> {
+    Some(RayHit2d { toi: 0.0, point: origin, normal: Vec2::ZERO })
+}
+
+/// Hits of a ray with the horizontal caps at `±half_height` of a shape with
+/// `base` as cross-section.
+fn cap_hits<B: Shape2dBase>(
+    origin: Vec3,
+    dir: Vec3,
+    base: &B,
+    half_height: f32,
+) -> impl Iterator<Item = (f32, Vec3)> + '_ {
+    let caps = [(-half_height, -Vec3::Y), (half_height, Vec3::Y)];
+    caps.into_iter().filter_map(move |(y, normal)| {
+        let toi = plane_y_hit(origin, dir, y)?;
+        let point = origin + dir * toi;
+        base.contains(point.xz()).then_some((toi, normal))
+    })
+}
+
+impl<B: Shape2dBase> Pyramid<B> {
+    /// When the base is curved, the sides are approximated.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let half_height = self.height / 2.0;
+        let apex = Vec3::Y * half_height;
+        let bottom = |v: Vec2| Vec3::new(v.x, -half_height, v.y);
+        let boundary = self.base.boundary(RAY_DENSITY);
+        let segments = boundary.iter().flat_map(|strip| strip.windows(2));
+        let walls =
+            segments.filter_map(|w| triangle_hit(origin, dir, [bottom(w[0]), bottom(w[1]), apex]));
+        let floor = cap_hits(origin, dir, &self.base, half_height);
+        let floor = floor.filter(|(_, normal)| normal.y < 0.0);
+        let hit = nearest(walls.chain(floor), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    /// When the base is curved, the sides are approximated.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let half_height = self.height / 2.0;
+        let bottom = |v: Vec2| Vec3::new(v.x, -half_height, v.y);
+        let top = |v: Vec2| Vec3::new(v.x, half_height, v.y);
+        let boundary = self.base.boundary(RAY_DENSITY);
+        let segments = boundary.iter().flat_map(|strip| strip.windows(2));
+        let walls = segments.flat_map(|w| {
+            let (a, b) = (w[0], w[1]);
+            let quad = [[bottom(a), bottom(b), top(a)], [top(a), bottom(b), top(b)]];
+            quad.into_iter()
+                .filter_map(|tri| triangle_hit(origin, dir, tri))
+        });
+        let caps = cap_hits(origin, dir, &self.base, half_height);
+        let hit = nearest(walls.chain(caps), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Sphere {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let hit = nearest(sphere_hits(origin, dir, Vec3::ZERO, self.radius), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl HalfSphere {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let r = self.radius;
+        let dome = sphere_hits(origin, dir, Vec3::ZERO, r);
+        let dome = dome.into_iter().filter(|(_, normal)| normal.y >= 0.0);
+        let disc = plane_y_hit(origin, dir, 0.0)
+            .filter(|toi| (origin + dir * *toi).xz().length_squared() <= r * r)
+            .map(|toi| (toi, -Vec3::Y));
+        let hit = nearest(dome.chain(disc), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Capsule {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let Capsule { radius, segment_height } = *self;
+        let half_height = segment_height / 2.0;
+        let offset = Vec3::Y * half_height;
+        let y_at = |toi: f32| origin.y + dir.y * toi;
+        let body = cylinder_hits(origin, dir, radius);
+        let body = body
+            .into_iter()
+            .filter(|(toi, _)| y_at(*toi).abs() <= half_height);
+        let top = sphere_hits(origin, dir, offset, radius);
+        let top = top.into_iter().filter(|(_, normal)| normal.y >= 0.0);
+        let bottom = sphere_hits(origin, dir, -offset, radius);
+        let bottom = bottom.into_iter().filter(|(_, normal)| normal.y <= 0.0);
+        let hit = nearest(body.chain(top).chain(bottom), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Cuboid {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let half_size = self.size / 2.0;
+        // A ray parallel to a pair of faces is either always or never between
+        // them, dividing by zero would give NaN when it starts on a face plane.
+        let parallel = dir.cmpeq(Vec3::ZERO);
+        if (parallel & origin.abs().cmpgt(half_size)).any() {
+            return None;
+        }
+        let (near, far) = ((-half_size - origin) / dir, (half_size - origin) / dir);
+        let (enter, exit) = (near.min(far), near.max(far));
+        let enter = Vec3::select(parallel, Vec3::splat(f32::NEG_INFINITY), enter);
+        let exit = Vec3::select(parallel, Vec3::splat(f32::INFINITY), exit);
+        let toi = enter.max_element();
+        if toi > exit.min_element() {
+            return None;
+        }
+        let axis = (0..3).find(|i| enter[*i] == toi)?;
+        let mut normal = Vec3::ZERO;
+        normal[axis] = -dir[axis].signum();
+        let hit = nearest([(toi, normal)], max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl RoundedCuboid {
+    /// The shape is the union of three cuboids, twelve cylinders along its
+    /// edges and eight spheres at its corners, the first hit is the first hit
+    /// on any of them.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let RoundedCuboid { size, bevel } = *self;
+        let inner = size / 2.0 - bevel;
+        let sign = |i: usize, bit| if i & bit == 0 { -1.0 } else { 1.0 };
+        let corners = (0..8).map(|i| Vec3::new(sign(i, 1), sign(i, 2), sign(i, 4)) * inner);
+        let corners: Vec<_> = corners.collect();
+
+        let cuboids = Vec3::AXES.map(|axis| Cuboid { size: (inner + axis * bevel) * 2.0 });
+        let cuboids = cuboids.map(|cuboid| cuboid.cast_ray(origin, dir, max_toi));
+        let cuboids = cuboids
+            .into_iter()
+            .flatten()
+            .map(|hit| (hit.toi, hit.normal));
+
+        // Cylinders along the Y axis, swap `axis` with Y to cast along it.
+        let swap = |v: Vec3, axis: usize| {
+            let mut v = v.to_array();
+            v.swap(axis, 1);
+            Vec3::from(v)
+        };
+        let edges = (0..3).flat_map(|axis| {
+            let cylinder = Cylinder { height: inner[axis] * 2.0, radius: bevel };
+            let centers = corners.iter().copied().filter(move |c| c[axis] > 0.0);
+            centers.filter_map(move |mut center| {
+                center[axis] = 0.0;
+                let local = swap(origin - center, axis);
+                let hit = cylinder.cast_ray(local, swap(dir, axis), max_toi)?;
+                Some((hit.toi, swap(hit.normal, axis)))
+            })
+        });
+        let sphere = Sphere { radius: bevel };
+        let spheres = corners.iter().filter_map(|corner| {
+            let hit = sphere.cast_ray(origin - *corner, dir, max_toi)?;
+            Some((hit.toi, hit.normal))
+        });
+        let hit = nearest(cuboids.chain(edges).chain(spheres), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Cone {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let Cone { height, base_radius } = *self;
+        let half_height = height / 2.0;
+        // The side is x² + z² = (k * (half_height - y))²
+        let k2 = (base_radius / height).powi(2);
+        let (o, d) = (origin, dir);
+        let (oy, dy) = (half_height - o.y, -d.y);
+        let roots = quadratic_roots(
+            d.x * d.x + d.z * d.z - k2 * dy * dy,
+            2.0 * (o.x * d.x + o.z * d.z - k2 * oy * dy),
+            o.x * o.x + o.z * o.z - k2 * oy * oy,
+        );
+        let side = roots.into_iter().flatten().filter_map(|toi| {
+            let p = origin + dir * toi;
+            let normal = Vec3::new(p.x, k2 * (half_height - p.y), p.z).normalize_or_zero();
+            (p.y.abs() <= half_height).then_some((toi, normal))
+        });
+        let base = plane_y_hit(origin, dir, -half_height)
+            .filter(|toi| (origin + dir * *toi).xz().length() <= base_radius)
+            .map(|toi| (toi, -Vec3::Y));
+        let hit = nearest(side.chain(base), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Tetrahedron {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let Tetrahedron { a, b, c, d } = *self;
+        let faces = [[a, b, c], [a, b, d], [a, c, d], [b, c, d]];
+        let hits = faces
+            .into_iter()
+            .filter_map(|f| triangle_hit(origin, dir, f));
+        let hit = nearest(hits, max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Cylinder {
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let half_height = self.height / 2.0;
+        let side = cylinder_hits(origin, dir, self.radius);
+        let in_height = |toi: &f32| (origin.y + dir.y * toi).abs() <= half_height;
+        let side = side.into_iter().filter(|(toi, _)| in_height(toi));
+        let disc = Disc { radius: self.radius };
+        let caps = cap_hits(origin, dir, &disc, half_height);
+        let hit = nearest(side.chain(caps), max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl Torus {
+    /// The surface of a torus is a quartic, rather than solving it, the ray
+    /// is sphere traced with [`Torus::sdf`] inside the bounding sphere. Rays
+    /// grazing the surface may miss it.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let Torus { major_radius, minor_radius } = *self;
+        let bounds = sphere_hits(origin, dir, Vec3::ZERO, major_radius + minor_radius);
+        let (enter, exit) = (bounds.first()?.0, bounds.last()?.0.min(max_toi));
+        let tolerance = TRACE_TOLERANCE * (major_radius + minor_radius);
+        let speed = dir.length();
+        let mut toi = enter.max(0.0);
+        for _ in 0..TRACE_STEPS {
+            if toi > exit {
+                return None;
+            }
+            let point = origin + dir * toi;
+            let distance = self.sdf(point);
+            if distance <= tolerance {
+                let ring = Vec3::new(point.x, 0.0, point.z).normalize_or_zero() * major_radius;
+                let normal = (point - ring).normalize_or_zero();
+                return Some(ray_hit(origin, dir, (toi, normal)));
+            }
+            toi += distance / speed;
+        }
+        None
+    }
+}
+impl Ellipsoid {
+    /// The ray is scaled down with the ellipsoid to hit a unit sphere.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return inside_hit(origin);
+        }
+        let axes = self.half_axes;
+        let hits = sphere_hits(origin / axes, dir / axes, Vec3::ZERO, 1.0);
+        let hits = hits
+            .into_iter()
+            .map(|(toi, normal)| (toi, (normal / axes).normalize()));
+        let hit = nearest(hits, max_toi)?;
+        Some(ray_hit(origin, dir, hit))
+    }
+}
+impl HeightField {
+    /// Cast a ray against the surface, from either side.
+    ///
+    /// This only checks the cells crossed by the ray.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_toi: f32) -> Option<RayHit> {
+        let (x_cells, y_cells) = self.cell_count();
+        let half_size = self.size / 2.0;
+        let cell_size = self.size / Vec2::new(x_cells as f32, y_cells as f32);
+        let (o, d) = (origin.xz(), dir.xz());
+
+        // Only consider the part of the ray above the grid.
+        let (near, far) = ((-half_size - o) / d, (half_size - o) / d);
+        let (near, far) = (near.min(far), near.max(far));
+        let enter = near.max_element().max(0.0);
+        let exit = far.min_element().min(max_toi);
+        let (enter, exit) = if d == Vec2::ZERO {
+            let above = o.abs().cmple(half_size).all();
+            (0.0, if above { max_toi } else { -1.0 })
+        } else {
+            (enter, exit)
+        };
+        if enter > exit {
+            return None;
+        }
+        // Walk the grid cells with a DDA, starting from the entry cell.
+        let start = (o + d * enter + half_size) / cell_size;
+        let max_cell = Vec2::new(x_cells as f32 - 1.0, y_cells as f32 - 1.0);
+        let cell = start.floor().clamp(Vec2::ZERO, max_cell);
+        let (mut x, mut y) = (cell.x as isize, cell.y as isize);
+        let step = d.signum();
+        let next_boundary = (cell + step.max(Vec2::ZERO)) * cell_size - half_size;
+        let mut next_toi = (next_boundary - o) / d;
+        let delta = (cell_size / d).abs();
+        loop {
+            let [left, right] = self.cell(x as usize, y as usize);
+            let hits = [left, right].map(|tri| triangle_hit(origin, dir, tri));
+            if let Some(hit) = nearest(hits.into_iter().flatten(), max_toi) {
+                return Some(ray_hit(origin, dir, hit));
+            }
+            let toi = if next_toi.x < next_toi.y {
+                x += step.x as isize;
+                next_toi.x += delta.x;
+                next_toi.x - delta.x
+            } else {
+                y += step.y as isize;
+                next_toi.y += delta.y;
+                next_toi.y - delta.y
+            };
+            let in_grid = (0..x_cells as isize).contains(&x) && (0..y_cells as isize).contains(&y);
+            if !in_grid || toi > exit || !toi.is_finite() {
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! [`Reflect`] for the shapes `#[derive(Reflect)]` can't handle.
+//!
+//! `bevy_reflect` doesn't support enums, so [`Shape2d`] is reflected as a
+//! struct with a single field named after its variant, for example
+//! `shape.disc.radius` is the radius of a `Shape2d::Disc`. Applying a struct
+//! with a field of another variant replaces the shape.
+//!
+//! [`Polygon`] and [`Lines`] are reflected as values, since `bevy_reflect`
+//! doesn't support arrays either. They can only be serialized with the
+//! `serde` feature.
+
+#[cfg(feature = "serde")]
+use bevy_reflect::ReflectDeserialize;
+use bevy_reflect::{
+    impl_from_reflect_value, impl_reflect_value, serde::Serializable, struct_partial_eq,
+    DynamicStruct, FieldIter, FromReflect, GetTypeRegistration, Reflect, ReflectMut, ReflectRef,
+    Struct, TypeRegistration, TypeRegistry,
+};
+
+use crate::*;
+
+#[cfg(feature = "serde")]
+impl_reflect_value!(Polygon(Serialize, Deserialize));
+#[cfg(not(feature = "serde"))]
+impl_reflect_value!(Polygon);
+impl_from_reflect_value!(Polygon);
+#[cfg(feature = "serde")]
+impl_reflect_value!(Lines(Serialize, Deserialize));
+#[cfg(not(feature = "serde"))]
+impl_reflect_value!(Lines);
+impl_from_reflect_value!(Lines);
+
+/// Register all the shapes of this crate, so that they can be used in
+/// scenes.
+pub fn register_types(registry: &mut TypeRegistry) {
+    registry.register::<Disc>();
+    registry.register::<HalfDisc>();
+    registry.register::<QuarterDisc>();
+    registry.register::<Annulus>();
+    registry.register::<Ellipse>();
+    registry.register::<Rectangle>();
+    registry.register::<RoundedRectangle>();
+    registry.register::<Triangle>();
+    registry.register::<Polygon>();
+    registry.register::<Shape2d>();
+    registry.register::<Pyramid>();
+    registry.register::<Extruded>();
+    registry.register::<Sphere>();
+    registry.register::<HalfSphere>();
+    registry.register::<Capsule>();
+    registry.register::<Cuboid>();
+    registry.register::<RoundedCuboid>();
+    registry.register::<Cone>();
+    registry.register::<Tetrahedron>();
+    registry.register::<Lines>();
+    registry.register::<Cylinder>();
+    registry.register::<Torus>();
+    registry.register::<Ellipsoid>();
+    registry.register::<HeightField>();
+}
+
+macro_rules! shape_2d_variants {
+    ($( $variant:ident => $field:literal ,)*) => {
+        impl Shape2d {
+            /// The name of the field of the current variant, and its value.
+            fn variant(&self) -> (&'static str, &dyn Reflect) {
+                match self {
+                    $(Shape2d::$variant(shape) => ($field, shape),)*
+                }
+            }
+            fn variant_mut(&mut self) -> (&'static str, &mut dyn Reflect) {
+                match self {
+                    $(Shape2d::$variant(shape) => ($field, shape),)*
+                }
+            }
+            /// The variant which field is `name`, built from `value`.
+            fn from_variant(name: &str, value: &dyn Reflect) -> Option<Self> {
+                match name {
+                    $($field => $variant::from_reflect(value).map(Shape2d::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+shape_2d_variants! {
+    Disc => "disc",
+    HalfDisc => "half_disc",
+    QuarterDisc => "quarter_disc",
+    Annulus => "annulus",
+    Ellipse => "ellipse",
+    Rectangle => "rectangle",
+    RoundedRectangle => "rounded_rectangle",
+    Triangle => "triangle",
+    Polygon => "polygon",
+}
+
+impl Struct for Shape2d {
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        let (variant, value) = self.variant();
+        (name == variant).then_some(value)
+    }
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let (variant, value) = self.variant_mut();
+        (name == variant).then_some(value)
+    }
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+        (index == 0).then(|| self.variant().1)
+    }
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        (index == 0).then(|| self.variant_mut().1)
+    }
+    fn name_at(&self, index: usize) -> Option<&str> {
+        (index == 0).then(|| self.variant().0)
+    }
+    fn field_len(&self) -> usize {
+        1
+    }
+    fn iter_fields(&self) -> FieldIter<'_> {
+        FieldIter::new(self)
+    }
+    fn clone_dynamic(&self) -> DynamicStruct {
+        let (variant, value) = self.variant();
+        let mut dynamic = DynamicStruct::default();
+        dynamic.set_name(self.type_name().to_string());
+        dynamic.insert_boxed(variant, value.clone_value());
+        dynamic
+    }
+}
+// SAFE: any and any_mut both return self
+unsafe impl Reflect for Shape2d {
+    fn type_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+    fn any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+    fn clone_value(&self) -> Box<dyn Reflect> {
+        Box::new(self.clone_dynamic())
+    }
+    fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        *self = value.take()?;
+        Ok(())
+    }
+    /// Apply the field of `value` to the current variant, or switch to the
+    /// variant of that field.
+    ///
+    /// `value` is ignored if it is not a struct.
+    fn apply(&mut self, value: &dyn Reflect) {
+        let value = match value.reflect_ref() {
+            ReflectRef::Struct(value) => value,
+            _ => return,
+        };
+        for (i, field) in value.iter_fields().enumerate() {
+            let name = value.name_at(i).unwrap();
+            if let Some(current) = self.field_mut(name) {
+                current.apply(field);
+            } else if let Some(shape) = Self::from_variant(name, field) {
+                *self = shape;
+            }
+        }
+    }
+    fn reflect_ref(&self) -> ReflectRef<'_> {
+        ReflectRef::Struct(self)
+    }
+    fn reflect_mut(&mut self) -> ReflectMut<'_> {
+        ReflectMut::Struct(self)
+    }
+    fn serializable(&self) -> Option<Serializable<'_>> {
+        None
+    }
+    fn reflect_hash(&self) -> Option<u64> {
+        None
+    }
+    fn reflect_partial_eq(&self, value: &dyn Reflect) -> Option<bool> {
+        struct_partial_eq(self, value)
+    }
+}
+impl FromReflect for Shape2d {
+    fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+        match reflect.reflect_ref() {
+            ReflectRef::Struct(value) => Self::from_variant(value.name_at(0)?, value.field_at(0)?),
+            _ => None,
+        }
+    }
+}
+impl GetTypeRegistration for Shape2d {
+    fn get_type_registration() -> TypeRegistration {
+        TypeRegistration::of::<Self>()
+    }
+}
+
+// `#[derive(Reflect)]` doesn't add the `B: Reflect` bound.
+macro_rules! reflect_base_and_height {
+    ($( $shape:ident ),*) => {$(
+        impl<B: Reflect> Struct for $shape<B> {
+            fn field(&self, name: &str) -> Option<&dyn Reflect> {
+                match name {
+                    "base" => Some(&self.base),
+                    "height" => Some(&self.height),
+                    _ => None,
+                }
+            }
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+                match name {
+                    "base" => Some(&mut self.base),
+                    "height" => Some(&mut self.height),
+                    _ => None,
+                }
+            }
+            fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+                match index {
+                    0 => Some(&self.base),
+                    1 => Some(&self.height),
+                    _ => None,
+                }
+            }
+            fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+                match index {
+                    0 => Some(&mut self.base),
+                    1 => Some(&mut self.height),
+                    _ => None,
+                }
+            }
+            fn name_at(&self, index: usize) -> Option<&str> {
+                ["base", "height"].get(index).copied()
+            }
+            fn field_len(&self) -> usize {
+                2
+            }
+            fn iter_fields(&self) -> FieldIter<'_> {
+                FieldIter::new(self)
+            }
+            fn clone_dynamic(&self) -> DynamicStruct {
+                let mut dynamic = DynamicStruct::default();
+                dynamic.set_name(self.type_name().to_string());
+                dynamic.insert_boxed("base", self.base.clone_value());
+                dynamic.insert_boxed("height", self.height.clone_value());
+                dynamic
+            }
+        }
+        // SAFE: any and any_mut both return self
+        unsafe impl<B: Reflect> Reflect for $shape<B> {
+            fn type_name(&self) -> &str {
+                std::any::type_name::<Self>()
+            }
+            fn any(&self) -> &dyn std::any::Any {
+                self
+            }
+            fn any_mut(&mut self) -> &mut dyn std::any::Any {
+                self
+            }
+            fn clone_value(&self) -> Box<dyn Reflect> {
+                Box::new(self.clone_dynamic())
+            }
+            fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+                *self = value.take()?;
+                Ok(())
+            }
+            /// `value` is ignored if it is not a struct.
+            fn apply(&mut self, value: &dyn Reflect) {
+                let value = match value.reflect_ref() {
+                    ReflectRef::Struct(value) => value,
+                    _ => return,
+                };
+                for (i, field) in value.iter_fields().enumerate() {
+                    let name = value.name_at(i).unwrap();
+                    if let Some(current) = self.field_mut(name) {
+                        current.apply(field);
+                    }
+                }
+            }
+            fn reflect_ref(&self) -> ReflectRef<'_> {
+                ReflectRef::Struct(self)
+            }
+            fn reflect_mut(&mut self) -> ReflectMut<'_> {
+                ReflectMut::Struct(self)
+            }
+            fn serializable(&self) -> Option<Serializable<'_>> {
+                None
+            }
+            fn reflect_hash(&self) -> Option<u64> {
+                None
+            }
+            fn reflect_partial_eq(&self, value: &dyn Reflect) -> Option<bool> {
+                struct_partial_eq(self, value)
+            }
+        }
+        impl<B: FromReflect> FromReflect for $shape<B> {
+            fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+                match reflect.reflect_ref() {
+                    ReflectRef::Struct(value) => Some($shape {
+                        base: B::from_reflect(value.field("base")?)?,
+                        height: f32::from_reflect(value.field("height")?)?,
+                    }),
+                    _ => None,
+                }
+            }
+        }
+        impl<B: Reflect> GetTypeRegistration for $shape<B> {
+            fn get_type_registration() -> TypeRegistration {
+                TypeRegistration::of::<Self>()
+            }
+        }
+    )*};
+}
+reflect_base_and_height!(Pyramid, Extruded);
@@ -0,0 +1,463 @@
+//! Uniformly distributed random points inside and on the surface of 3d
+//! shapes.
+//!
+//! 2d shapes implement it with [`Shape2dBase::sample_interior`] and
+//! [`Shape2dBase::sample_boundary`].
+
+use std::f32::consts::{PI, TAU};
+
+use bevy_math::{BVec2, BVec3, Vec2, Vec3};
+use rand::{Rng, RngCore};
+
+use crate::measure::triangle_area;
+use crate::*;
+
+/// The `density` used to approximate curved shapes when sampling their
+/// triangles or boundary.
+pub(crate) const SAMPLE_DENSITY: usize = 32;
+
+/// Index of one of `weights`, with a probability proportional to its weight.
+///
+/// # Panics
+///
+/// If `weights` is empty, this happens when sampling a shape with no
+/// triangles or lines.
+pub(crate) fn pick(weights: &[f32], rng: &mut dyn RngCore) -> usize {
+    assert!(!weights.is_empty(), "Can't sample an empty shape");
+    let mut target = rng.gen::<f32>() * weights.iter().sum::<f32>();
+    for (i, weight) in weights.iter().enumerate() {
+        if target < *weight {
+            return i;
+        }
+        target -= weight;
+    }
+    // Rounding errors may leave a bit of `target`.
+    weights.len() - 1
+}
+/// Uniformly distributed between `-half` and `half`.
+pub(crate) fn symmetric(half: f32, rng: &mut dyn RngCore) -> f32 {
+    (rng.gen::<f32>() * 2.0 - 1.0) * half
+}
+pub(crate) fn random_sign(rng: &mut dyn RngCore) -> f32 {
+    if rng.gen() {
+        1.0
+    } else {
+        -1.0
+    }
+}
+/// Barycentric coordinates of `b` and `c` for a point of a triangle.
+fn triangle_coords(rng: &mut dyn RngCore) -> (f32, f32) {
+    let (u, v): (f32, f32) = rng.gen();
+    // Points outside the triangle are folded back in it.
+    if u + v > 1.0 {
+        (1.0 - u, 1.0 - v)
+    } else {
+        (u, v)
+    }
+}
+pub(crate) fn in_triangle_2d([a, b, c]: [Vec2; 3], rng: &mut dyn RngCore) -> Vec2 {
+    let (u, v) = triangle_coords(rng);
+    a + (b - a) * u + (c - a) * v
+}
+/// A point in one of `tris`, weighted by their area.
+fn in_triangles(tris: &[[Vec3; 3]], rng: &mut dyn RngCore) -> Vec3 {
+    let weights: Vec<_> = tris.iter().copied().map(triangle_area).collect();
+    let [a, b, c] = tris[pick(&weights, rng)];
+    let (u, v) = triangle_coords(rng);
+    a + (b - a) * u + (c - a) * v
+}
+/// A point on the unit circle.
+pub(crate) fn on_circle(rng: &mut dyn RngCore) -> Vec2 {
+    let angle = rng.gen::<f32>() * TAU;
+    Vec2::new(angle.cos(), angle.sin())
+}
+/// A point in the unit disc.
+///
+/// The square root compensates for the circumference growing with the
+/// distance to the center.
+pub(crate) fn in_disc(rng: &mut dyn RngCore) -> Vec2 {
+    on_circle(rng) * rng.gen::<f32>().sqrt()
+}
+/// A point on the unit sphere.
+///
+/// The height is uniform, since slices of a sphere of the same thickness
+/// have the same area.
+fn on_sphere(rng: &mut dyn RngCore) -> Vec3 {
+    let y = symmetric(1.0, rng);
+    let around = on_circle(rng) * (1.0 - y * y).sqrt();
+    Vec3::new(around.x, y, around.y)
+}
+/// A point in the unit ball.
+fn in_ball(rng: &mut dyn RngCore) -> Vec3 {
+    on_sphere(rng) * rng.gen::<f32>().cbrt()
+}
+/// `point` in the XZ plane, at height `y`.
+fn xz(point: Vec2, y: f32) -> Vec3 {
+    Vec3::new(point.x, y, point.y)
+}
+fn only(axis: usize) -> BVec3 {
+    BVec3::new(axis == 0, axis == 1, axis == 2)
+}
+/// `offset` placed on the 2 axes other than `axis`.
+fn around_axis(axis: usize, offset: Vec2) -> Vec3 {
+    let mut point = Vec3::ZERO;
+    point[(axis + 1) % 3] = offset.x;
+    point[(axis + 2) % 3] = offset.y;
+    point
+}
+
+/// A point in the cuboid of half size `inner` moved by `offset` on the
+/// `rounded` axes, away from the center.
+///
+/// With `offset` in a ball of radius `bevel`, this is a point in a corner of
+/// a [`RoundedCuboid`]. On 2 axes, in an edge, on 1 axis, in a face.
+fn rounded(inner: Vec3, rounded: BVec3, offset: Vec3, rng: &mut dyn RngCore) -> Vec3 {
+    let in_inner = Vec3::new(
+        symmetric(inner.x, rng),
+        symmetric(inner.y, rng),
+        symmetric(inner.z, rng),
+    );
+    Vec3::select(rounded, inner * offset.signum() + offset, in_inner)
+}
+/// 2d version of [`rounded`], for [`RoundedRectangle`].
+pub(crate) fn rounded_2d(inner: Vec2, rounded: BVec2, offset: Vec2, rng: &mut dyn RngCore) -> Vec2 {
+    let in_inner = Vec2::new(symmetric(inner.x, rng), symmetric(inner.y, rng));
+    Vec2::select(rounded, inner * offset.signum() + offset, in_inner)
+}
+
+impl<B: Shape2dBase> Pyramid<B> {
+    /// A random point inside the pyramid, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        // The area of a slice grows with the square of its distance to the
+        // apex, so the distance is the cube root of a uniform value.
+        let scale = rng.gen::<f32>().cbrt();
+        let point = self.base.sample_interior(rng) * scale;
+        xz(point, self.height / 2.0 - scale * self.height)
+    }
+    /// A random point on the surface of the pyramid, all points are equally
+    /// likely.
+    ///
+    /// When the base is curved, the sides are approximated.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let half_height = self.height / 2.0;
+        let apex = Vec3::Y * half_height;
+        let boundary = self.base.boundary(SAMPLE_DENSITY);
+        let segments = boundary.iter().flat_map(|strip| strip.windows(2));
+        let sides: Vec<_> = segments
+            .map(|w| [xz(w[0], -half_height), xz(w[1], -half_height), apex])
+            .collect();
+        let side_area: f32 = sides.iter().copied().map(triangle_area).sum();
+        match pick(&[self.base.area(), side_area], rng) {
+            0 => xz(self.base.sample_interior(rng), -half_height),
+            _ => in_triangles(&sides, rng),
+        }
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    /// A random point inside the extruded shape, all points are equally
+    /// likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let point = self.base.sample_interior(rng);
+        xz(point, symmetric(self.height / 2.0, rng))
+    }
+    /// A random point on the surface of the extruded shape, all points are
+    /// equally likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let caps = 2.0 * self.base.area();
+        let side = self.base.perimeter() * self.height;
+        let half_height = self.height / 2.0;
+        match pick(&[caps, side], rng) {
+            0 => xz(
+                self.base.sample_interior(rng),
+                random_sign(rng) * half_height,
+            ),
+            _ => xz(self.base.sample_boundary(rng), symmetric(half_height, rng)),
+        }
+    }
+}
+impl Sphere {
+    /// A random point inside the sphere, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        in_ball(rng) * self.radius
+    }
+    /// A random point on the surface of the sphere, all points are equally
+    /// likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        on_sphere(rng) * self.radius
+    }
+}
+impl HalfSphere {
+    /// A random point inside the half sphere, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let point = in_ball(rng) * self.radius;
+        Vec3::new(point.x, point.y.abs(), point.z)
+    }
+    /// A random point on the dome or the flat disc of the half sphere, all
+    /// points are equally likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        // The dome has twice the area of the disc.
+        match pick(&[2.0, 1.0], rng) {
+            0 => {
+                let point = on_sphere(rng) * self.radius;
+                Vec3::new(point.x, point.y.abs(), point.z)
+            }
+            _ => xz(in_disc(rng) * self.radius, 0.0),
+        }
+    }
+}
+impl Capsule {
+    /// A random point inside the capsule, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Capsule { radius, segment_height } = *self;
+        let body = PI * radius * radius * segment_height;
+        let caps = 4.0 / 3.0 * PI * radius.powi(3);
+        let half_segment = segment_height / 2.0;
+        match pick(&[body, caps], rng) {
+            0 => xz(in_disc(rng) * radius, symmetric(half_segment, rng)),
+            _ => {
+                let point = in_ball(rng) * radius;
+                point + Vec3::Y * point.y.signum() * half_segment
+            }
+        }
+    }
+    /// A random point on the surface of the capsule, all points are equally
+    /// likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Capsule { radius, segment_height } = *self;
+        let body = TAU * radius * segment_height;
+        let caps = 4.0 * PI * radius * radius;
+        let half_segment = segment_height / 2.0;
+        match pick(&[body, caps], rng) {
+            0 => xz(on_circle(rng) * radius, symmetric(half_segment, rng)),
+            _ => {
+                let point = on_sphere(rng) * radius;
+                point + Vec3::Y * point.y.signum() * half_segment
+            }
+        }
+    }
+}
+impl Cuboid {
+    /// A random point inside the cuboid, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let half_size = self.size / 2.0;
+        rounded(half_size, BVec3::new(false, false, false), Vec3::ZERO, rng)
+    }
+    /// A random point on the surface of the cuboid, all points are equally
+    /// likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let [x, y, z] = self.size.to_array();
+        let axis = pick(&[y * z, x * z, x * y], rng);
+        let mut point = self.sample_interior(rng);
+        point[axis] = random_sign(rng) * self.size[axis] / 2.0;
+        point
+    }
+}
+impl RoundedCuboid {
+    /// A random point inside the cuboid, all points are equally likely.
+    ///
+    /// The cuboid is split into the inner cuboid, the 6 faces, the 12 edges
+    /// and the 8 corners, which are sampled according to their volume.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let bevel = self.bevel;
+        let inner = self.size / 2.0 - bevel;
+        let [x, y, z] = (inner * 2.0).to_array();
+        let edge = PI * bevel * bevel;
+        let weights = [
+            x * y * z,
+            2.0 * bevel * y * z,
+            2.0 * bevel * x * z,
+            2.0 * bevel * x * y,
+            edge * x,
+            edge * y,
+            edge * z,
+            4.0 / 3.0 * PI * bevel.powi(3),
+        ];
+        let (axes, offset) = match pick(&weights, rng) {
+            0 => (BVec3::new(false, false, false), Vec3::ZERO),
+            face @ 1..=3 => {
+                let axis = face - 1;
+                let mut offset = Vec3::ZERO;
+                offset[axis] = symmetric(bevel, rng);
+                (only(axis), offset)
+            }
+            edge @ 4..=6 => {
+                let axis = edge - 4;
+                (!only(axis), around_axis(axis, in_disc(rng) * bevel))
+            }
+            _ => (BVec3::new(true, true, true), in_ball(rng) * bevel),
+        };
+        rounded(inner, axes, offset, rng)
+    }
+    /// A random point on the surface of the cuboid, all points are equally
+    /// likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let bevel = self.bevel;
+        let inner = self.size / 2.0 - bevel;
+        let [x, y, z] = (inner * 2.0).to_array();
+        let edge = TAU * bevel;
+        let weights = [
+            2.0 * y * z,
+            2.0 * x * z,
+            2.0 * x * y,
+            edge * x,
+            edge * y,
+            edge * z,
+            4.0 * PI * bevel * bevel,
+        ];
+        let (axes, offset) = match pick(&weights, rng) {
+            axis @ 0..=2 => {
+                // With a `bevel` of 0, the sign of the zero still picks the
+                // face.
+                let mut offset = Vec3::ZERO;
+                offset[axis] = random_sign(rng) * bevel;
+                (only(axis), offset)
+            }
+            edge @ 3..=5 => {
+                let axis = edge - 3;
+                (!only(axis), around_axis(axis, on_circle(rng) * bevel))
+            }
+            _ => (BVec3::new(true, true, true), on_sphere(rng) * bevel),
+        };
+        rounded(inner, axes, offset, rng)
+    }
+}
+impl Cone {
+    /// A random point inside the cone, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let scale = rng.gen::<f32>().cbrt();
+        let point = in_disc(rng) * self.base_radius * scale;
+        xz(point, self.height / 2.0 - scale * self.height)
+    }
+    /// A random point on the base or the side of the cone, all points are
+    /// equally likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Cone { height, base_radius: r } = *self;
+        let base = PI * r * r;
+        let side = PI * r * (r * r + height * height).sqrt();
+        match pick(&[base, side], rng) {
+            0 => xz(in_disc(rng) * r, -height / 2.0),
+            _ => {
+                // The circumference of the side grows linearly with the
+                // distance to the apex.
+                let scale = rng.gen::<f32>().sqrt();
+                xz(on_circle(rng) * r * scale, height / 2.0 - scale * height)
+            }
+        }
+    }
+}
+impl Tetrahedron {
+    /// A random point inside the tetrahedron, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Tetrahedron { a, b, c, d } = *self;
+        // The gaps between 3 sorted uniform values are uniformly distributed
+        // barycentric coordinates.
+        let mut cuts: [f32; 3] = rng.gen();
+        cuts.sort_by(f32::total_cmp);
+        let [u, v, w] = cuts;
+        a * u + b * (v - u) + c * (w - v) + d * (1.0 - w)
+    }
+    /// A random point on the faces of the tetrahedron, all points are equally
+    /// likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Tetrahedron { a, b, c, d } = *self;
+        in_triangles(&[[a, b, c], [a, b, d], [a, c, d], [b, c, d]], rng)
+    }
+}
+impl Lines {
+    /// A random point on the lines, all points are equally likely.
+    ///
+    /// Lines have no interior, so there is no `sample_interior`.
+    ///
+    /// # Panics
+    ///
+    /// If there are no `lines`.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let points = &self.points;
+        let length = |[a, b]: &[usize; 2]| points[*a].distance(points[*b]);
+        let weights: Vec<_> = self.lines.iter().map(length).collect();
+        let [a, b] = self.lines[pick(&weights, rng)];
+        points[a].lerp(points[b], rng.gen())
+    }
+}
+impl Cylinder {
+    /// A random point inside the cylinder, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        xz(
+            in_disc(rng) * self.radius,
+            symmetric(self.height / 2.0, rng),
+        )
+    }
+    /// A random point on the caps or the side of the cylinder, all points are
+    /// equally likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Cylinder { height, radius } = *self;
+        let caps = TAU * radius * radius;
+        let side = TAU * radius * height;
+        match pick(&[caps, side], rng) {
+            0 => xz(in_disc(rng) * radius, random_sign(rng) * height / 2.0),
+            _ => xz(on_circle(rng) * radius, symmetric(height / 2.0, rng)),
+        }
+    }
+}
+impl Torus {
+    /// A random point inside the torus, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Torus { major_radius, minor_radius } = *self;
+        // The tube is thicker on the outside of the ring, so points of the
+        // cross-section are kept with a probability proportional to their
+        // distance to the axis.
+        loop {
+            let section = in_disc(rng) * minor_radius;
+            let distance = (major_radius + section.x).abs();
+            if rng.gen::<f32>() * (major_radius + minor_radius) <= distance {
+                return (xz(on_circle(rng), 0.0) * distance) + Vec3::Y * section.y;
+            }
+        }
+    }
+    /// A random point on the surface of the torus, all points are equally
+    /// likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let Torus { major_radius, minor_radius } = *self;
+        // See `sample_interior`.
+        loop {
+            let section = on_circle(rng) * minor_radius;
+            let distance = (major_radius + section.x).abs();
+            if rng.gen::<f32>() * (major_radius + minor_radius) <= distance {
+                return (xz(on_circle(rng), 0.0) * distance) + Vec3::Y * section.y;
+            }
+        }
+    }
+}
+impl Ellipsoid {
+    /// A random point inside the ellipsoid, all points are equally likely.
+    pub fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec3 {
+        in_ball(rng) * self.half_axes
+    }
+    /// A random point on the surface of the ellipsoid, all points are
+    /// equally likely.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let [x, y, z] = self.half_axes.to_array();
+        // Stretching the unit sphere scales its area by `stretch.length()`
+        // at `point`, so points are kept with a probability proportional to it.
+        let scales = Vec3::new(y * z, x * z, x * y);
+        loop {
+            let point = on_sphere(rng);
+            let stretch = point * scales;
+            if rng.gen::<f32>() * scales.max_element() <= stretch.length() {
+                return point * self.half_axes;
+            }
+        }
+    }
+}
+impl HeightField {
+    /// A random point on the surface, all points are equally likely.
+    ///
+    /// Steep cells have a larger area, so they get more points than flat
+    /// ones. Height fields have no interior, so there is no
+    /// `sample_interior`.
+    pub fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec3 {
+        let mut tris = Vec::new();
+        self.for_each_cell(|left, right| tris.extend([left, right]));
+        in_triangles(&tris, rng)
+    }
+}
@@ -0,0 +1,222 @@
+//! Signed distance functions of 3d shapes.
+//!
+//! Distances are negative inside the shapes. 2d shapes implement it with
+//! [`Shape2dBase::sdf`].
+
+use bevy_math::{Vec2, Vec3, Vec3Swizzles};
+
+use crate::*;
+
+/// The `density` used to approximate curved bases of [`Pyramid`].
+const SDF_DENSITY: usize = 32;
+
+/// The point of the `[start, end]` segment closest to `point`.
+pub(crate) fn closest_on_segment(point: Vec3, [start, end]: [Vec3; 2]) -> Vec3 {
+    let [u, v] = segment_barycentric(point, [start, end]);
+    start * u + end * v
+}
+/// The barycentric coordinates of the point of the `[start, end]` segment
+/// closest to `point`.
+pub(crate) fn segment_barycentric(point: Vec3, [start, end]: [Vec3; 2]) -> [f32; 2] {
+    let segment = end - start;
+    let t = (point - start).dot(segment) / segment.length_squared();
+    let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 };
+    [1.0 - t, t]
+}
+/// The point of the `[a, b, c]` triangle closest to `point`.
+pub(crate) fn closest_on_triangle(point: Vec3, [a, b, c]: [Vec3; 3]) -> Vec3 {
+    let [u, v, w] = triangle_barycentric(point, [a, b, c]);
+    a * u + b * v + c * w
+}
+/// The barycentric coordinates of the point of the `[a, b, c]` triangle
+/// closest to `point`.
+///
+/// From "Real-Time Collision Detection" by Christer Ericson, 5.1.5.
+pub(crate) fn triangle_barycentric(point: Vec3, [a, b, c]: [Vec3; 3]) -> [f32; 3] {
+    let (ab, ac, ap) = (b - a, c - a, point - a);
+    let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return [1.0, 0.0, 0.0];
+    }
+    let bp = point - b;
+    let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return [0.0, 1.0, 0.0];
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return [1.0 - v, v, 0.0];
+    }
+    let cp = point - c;
+    let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return [0.0, 0.0, 1.0];
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return [1.0 - w, 0.0, w];
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return [0.0, 1.0 - w, w];
+    }
+    let denom = 1.0 / (va + vb + vc);
+    let (v, w) = (vb * denom, vc * denom);
+    [1.0 - v - w, v, w]
+}
+fn mesh_distance(point: Vec3, tris: impl IntoIterator<Item = [Vec3; 3]>) -> f32 {
+    let distance = |tri| point.distance(closest_on_triangle(point, tri));
+    tris.into_iter().map(distance).fold(f32::INFINITY, f32::min)
+}
+fn segment_distance_2d(point: Vec2, [start, end]: [Vec2; 2]) -> f32 {
+    let closest = closest_on_segment(point.extend(0.0), [start.extend(0.0), end.extend(0.0)]);
+    point.distance(closest.truncate())
+}
+fn signed(distance: f32, inside: bool) -> f32 {
+    if inside {
+        -distance
+    } else {
+        distance
+    }
+}
+/// Approximate distance to an ellipse or ellipsoid, from the length of the
+/// point divided by the half axes (`scaled`) and by their square (`gradient`).
+pub(crate) fn ellipsoid_sdf(scaled: f32, gradient: f32, smallest_axis: f32) -> f32 {
+    if gradient == 0.0 {
+        -smallest_axis
+    } else {
+        scaled * (scaled - 1.0) / gradient
+    }
+}
+/// Distance from a point to the axis of a shape of revolution around Y, and
+/// its height.
+fn profile(point: Vec3) -> Vec2 {
+    Vec2::new(point.xz().length(), point.y)
+}
+
+impl<B: Shape2dBase> Pyramid<B> {
+    /// When the base is curved, the distance is approximated.
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let half_height = Vec3::Y * self.height / 2.0;
+        let bottom = |v: Vec2| Vec3::new(v.x, 0.0, v.y) - half_height;
+        let floor = self.base.triangulate(SDF_DENSITY);
+        let floor = floor.into_iter().map(|tri| tri.map(bottom));
+        let boundary = self.base.boundary(SDF_DENSITY);
+        let segments = boundary.iter().flat_map(|strip| strip.windows(2));
+        let walls = segments.map(|w| [bottom(w[0]), bottom(w[1]), half_height]);
+        let distance = mesh_distance(point, floor.chain(walls));
+        signed(distance, self.contains(point))
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let w = Vec2::new(self.base.sdf(point.xz()), point.y.abs() - self.height / 2.0);
+        w.max_element().min(0.0) + w.max(Vec2::ZERO).length()
+    }
+}
+impl Sphere {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        point.length() - self.radius
+    }
+}
+impl HalfSphere {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let r = self.radius;
+        let p = profile(point);
+        let rim = Vec2::new(r, 0.0);
+        let dome = if p.y >= 0.0 {
+            (p.length() - r).abs()
+        } else {
+            p.distance(rim)
+        };
+        let disc = segment_distance_2d(p, [Vec2::ZERO, rim]);
+        signed(dome.min(disc), self.contains(point))
+    }
+}
+impl Capsule {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let half_segment = Vec3::Y * self.segment_height / 2.0;
+        let on_segment = closest_on_segment(point, [-half_segment, half_segment]);
+        point.distance(on_segment) - self.radius
+    }
+}
+impl Cuboid {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let d = point.abs() - self.size / 2.0;
+        d.max(Vec3::ZERO).length() + d.max_element().min(0.0)
+    }
+}
+impl RoundedCuboid {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let d = point.abs() - (self.size / 2.0 - self.bevel);
+        d.max(Vec3::ZERO).length() + d.max_element().min(0.0) - self.bevel
+    }
+}
+impl Cone {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let half_height = self.height / 2.0;
+        let p = profile(point);
+        let (center, rim) = (
+            Vec2::new(0.0, -half_height),
+            Vec2::new(self.base_radius, -half_height),
+        );
+        let base = segment_distance_2d(p, [center, rim]);
+        let side = segment_distance_2d(p, [rim, Vec2::new(0.0, half_height)]);
+        signed(base.min(side), self.contains(point))
+    }
+}
+impl Tetrahedron {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let Tetrahedron { a, b, c, d } = *self;
+        let distance = mesh_distance(point, [[a, b, c], [a, b, d], [a, c, d], [b, c, d]]);
+        signed(distance, self.contains(point))
+    }
+}
+impl Lines {
+    /// Distance to the closest line, this is never negative.
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let points = &self.points;
+        let distance = |[a, b]: &[usize; 2]| {
+            point.distance(closest_on_segment(point, [points[*a], points[*b]]))
+        };
+        self.lines
+            .iter()
+            .map(distance)
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+impl Cylinder {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let p = profile(point);
+        let d = Vec2::new(p.x - self.radius, p.y.abs() - self.height / 2.0);
+        d.max_element().min(0.0) + d.max(Vec2::ZERO).length()
+    }
+}
+impl Ellipsoid {
+    /// The distance is approximated, it is exact on the surface and when all
+    /// `half_axes` are equal.
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let axes = self.half_axes;
+        let scaled = (point / axes).length();
+        ellipsoid_sdf(scaled, (point / (axes * axes)).length(), axes.min_element())
+    }
+}
+impl Torus {
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let p = profile(point);
+        Vec2::new(p.x - self.major_radius, p.y).length() - self.minor_radius
+    }
+}
+impl HeightField {
+    /// Distance to the surface, this is never negative.
+    pub fn sdf(&self, point: Vec3) -> f32 {
+        let mut distance = f32::INFINITY;
+        self.for_each_cell(|left, right| {
+            distance = distance.min(mesh_distance(point, [left, right]));
+        });
+        distance
+    }
+}
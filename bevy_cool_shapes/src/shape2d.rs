@@ -0,0 +1,862 @@
+use std::f32::consts::{PI, TAU};
+use std::iter;
+
+#[cfg(feature = "rand")]
+use bevy_math::BVec2;
+use bevy_math::{Mat2, Vec2};
+#[cfg(feature = "rand")]
+use rand::{Rng, RngCore};
+
+use crate::ray::{circle_hits_2d, inside_hit_2d, nearest_2d, segment_hit_2d};
+#[cfg(feature = "rand")]
+use crate::sample::{self, SAMPLE_DENSITY};
+use crate::sdf::ellipsoid_sdf;
+use crate::validate::check_inner_radius;
+use crate::validate::{check_bevel, check_dimension, check_half_axes, check_indices, check_points};
+use crate::{Annulus, Disc, Ellipse, HalfDisc, Polygon, QuarterDisc, RayHit2d};
+use crate::{Rectangle, RoundedRectangle, Shape2d, ShapeError, Triangle};
+
+/// A 2d shape usable as the base of [`Pyramid`] and [`Extruded`].
+///
+/// Curved shapes are approximated with line segments, `density` is how many
+/// segments are used for a quarter of a circle.
+///
+/// [`Pyramid`]: crate::Pyramid
+/// [`Extruded`]: crate::Extruded
+pub trait Shape2dBase {
+    /// Line strips delimiting the shape.
+    ///
+    /// Closed loops repeat their first point at the end.
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>>;
+
+    /// Triangles covering the surface of the shape.
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]>;
+
+    /// The point of the shape the furthest in `direction`.
+    fn support(&self, direction: Vec2) -> Vec2;
+
+    /// The surface covered by the shape.
+    ///
+    /// By default, this is the area of the triangles of `triangulate`, so
+    /// curved shapes are approximated.
+    fn area(&self) -> f32 {
+        let tris = self.triangulate(DEFAULT_DENSITY);
+        tris.into_iter().map(triangle_area).sum()
+    }
+
+    /// The length of the boundary of the shape.
+    ///
+    /// By default, this is the length of the segments of `boundary`.
+    fn perimeter(&self) -> f32 {
+        let boundary = self.boundary(DEFAULT_DENSITY);
+        segments(&boundary).map(|[a, b]| a.distance(b)).sum()
+    }
+
+    /// Whether `point` is inside the shape or on its boundary.
+    ///
+    /// By default, whether `point` is in one of the triangles of
+    /// `triangulate`.
+    fn contains(&self, point: Vec2) -> bool {
+        let mut tris = self.triangulate(DEFAULT_DENSITY).into_iter();
+        tris.any(|tri| triangle_contains(tri, point))
+    }
+
+    /// Signed distance from `point` to the boundary of the shape, negative
+    /// inside the shape.
+    ///
+    /// By default, this is the distance to the closest segment of `boundary`,
+    /// negative if the shape `contains` the point.
+    fn sdf(&self, point: Vec2) -> f32 {
+        let boundary = self.boundary(DEFAULT_DENSITY);
+        let distances = segments(&boundary).map(|segment| segment_distance(point, segment));
+        signed(
+            distances.fold(f32::INFINITY, f32::min),
+            self.contains(point),
+        )
+    }
+
+    /// The first point where the ray from `origin` toward `dir` hits the
+    /// shape, within `max_toi` times `dir`.
+    ///
+    /// Rays starting inside the shape hit it at their origin, with a zero
+    /// normal.
+    ///
+    /// By default, the ray is cast against the segments of `boundary`.
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let boundary = self.boundary(DEFAULT_DENSITY);
+        let hits = segments(&boundary).filter_map(|segment| segment_hit_2d(origin, dir, segment));
+        nearest_2d(origin, dir, max_toi, hits)
+    }
+
+    /// Check that the shape is well-formed, see [`ShapeError`].
+    ///
+    /// By default, all shapes are valid.
+    fn validate(&self) -> Result<(), ShapeError> {
+        Ok(())
+    }
+
+    /// A random point inside the shape, all points are equally likely.
+    ///
+    /// By default, this picks a point in the triangles of `triangulate`,
+    /// weighted by their area, so curved shapes are approximated.
+    ///
+    /// # Panics
+    ///
+    /// If the shape has no triangles, for example a [`Polygon`] without
+    /// `tris`.
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let tris = self.triangulate(SAMPLE_DENSITY);
+        let weights: Vec<_> = tris.iter().copied().map(triangle_area).collect();
+        sample::in_triangle_2d(tris[sample::pick(&weights, rng)], rng)
+    }
+
+    /// A random point on the boundary of the shape, all points are equally
+    /// likely.
+    ///
+    /// By default, this picks a point on the segments of `boundary`,
+    /// weighted by their length, so curved shapes are approximated.
+    ///
+    /// # Panics
+    ///
+    /// If the shape has no boundary, for example a [`Polygon`] without
+    /// `lines`.
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let boundary = self.boundary(SAMPLE_DENSITY);
+        let segments: Vec<_> = segments(&boundary).collect();
+        let weights: Vec<_> = segments.iter().map(|[a, b]| a.distance(*b)).collect();
+        let [a, b] = segments[sample::pick(&weights, rng)];
+        a.lerp(b, rng.gen())
+    }
+}
+
+/// The `density` used by the default methods of [`Shape2dBase`] to
+/// approximate curved shapes.
+const DEFAULT_DENSITY: usize = 32;
+
+/// Points on a circle, starting at `start`, rotating counter-clockwise by a
+/// quarter of a circle every `density` points.
+fn circle(start: Vec2, density: usize) -> impl Iterator<Item = Vec2> {
+    let segment_rotation = Mat2::from_angle(TAU / 4.0 / density as f32);
+    iter::successors(Some(start), move |v| Some(segment_rotation * *v))
+}
+
+/// Triangles between the origin and each segment of the boundary.
+fn fan(boundary: Vec<Vec<Vec2>>) -> Vec<[Vec2; 3]> {
+    let fan_loop = |loop_: Vec<Vec2>| {
+        let tris = loop_.windows(2).map(|w| [Vec2::ZERO, w[0], w[1]]);
+        tris.collect::<Vec<_>>()
+    };
+    boundary.into_iter().flat_map(fan_loop).collect()
+}
+
+/// The segments of the line strips of `boundary`.
+fn segments(boundary: &[Vec<Vec2>]) -> impl Iterator<Item = [Vec2; 2]> + '_ {
+    let segments = boundary.iter().flat_map(|strip| strip.windows(2));
+    segments.map(|w| [w[0], w[1]])
+}
+/// The point of `points` the furthest in `direction`.
+fn max_dot(points: impl IntoIterator<Item = Vec2>, direction: Vec2) -> Vec2 {
+    let furthest = |a: Vec2, b: Vec2| if a.dot(direction) >= b.dot(direction) { a } else { b };
+    points.into_iter().reduce(furthest).unwrap_or(Vec2::ZERO)
+}
+
+fn triangle_area([a, b, c]: [Vec2; 3]) -> f32 {
+    (b - a).perp_dot(c - a).abs() / 2.0
+}
+
+fn triangle_contains([a, b, c]: [Vec2; 3], point: Vec2) -> bool {
+    let sides = [(a, b), (b, c), (c, a)].map(|(a, b)| (b - a).perp_dot(point - a));
+    sides.iter().all(|side| *side >= 0.0) || sides.iter().all(|side| *side <= 0.0)
+}
+
+/// Distance from `point` to the `[start, end]` segment.
+fn segment_distance(point: Vec2, [start, end]: [Vec2; 2]) -> f32 {
+    let segment = end - start;
+    let t = (point - start).dot(segment) / segment.length_squared();
+    let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 };
+    point.distance(start + segment * t)
+}
+fn sign(v: f32) -> f32 {
+    if v >= 0.0 {
+        1.0
+    } else {
+        -1.0
+    }
+}
+fn signed(distance: f32, inside: bool) -> f32 {
+    if inside {
+        -distance
+    } else {
+        distance
+    }
+}
+
+impl Shape2dBase for Disc {
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        let start = -Vec2::X * self.radius;
+        vec![circle(start, density).take(density * 4 + 1).collect()]
+    }
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        fan(self.boundary(density))
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        direction.normalize_or_zero() * self.radius
+    }
+    fn area(&self) -> f32 {
+        PI * self.radius * self.radius
+    }
+    fn perimeter(&self) -> f32 {
+        TAU * self.radius
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        point.length_squared() <= self.radius * self.radius
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        point.length() - self.radius
+    }
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        nearest_2d(
+            origin,
+            dir,
+            max_toi,
+            circle_hits_2d(origin, dir, self.radius),
+        )
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("radius", self.radius)
+    }
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        sample::in_disc(rng) * self.radius
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        sample::on_circle(rng) * self.radius
+    }
+}
+impl Shape2dBase for HalfDisc {
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        let start = Vec2::X * self.radius;
+        let arc = circle(start, density).take(density * 2 + 1);
+        vec![arc.chain(iter::once(start)).collect()]
+    }
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        fan(self.boundary(density))
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        if direction.y > 0.0 {
+            direction.normalize() * self.radius
+        } else {
+            let corner = Vec2::X * self.radius;
+            max_dot([corner, -corner], direction)
+        }
+    }
+    fn area(&self) -> f32 {
+        PI * self.radius * self.radius / 2.0
+    }
+    fn perimeter(&self) -> f32 {
+        (PI + 2.0) * self.radius
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        point.y >= 0.0 && point.length_squared() <= self.radius * self.radius
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        let r = self.radius;
+        let arc = if point.y >= 0.0 {
+            (point.length() - r).abs()
+        } else {
+            point
+                .distance(Vec2::new(r, 0.0))
+                .min(point.distance(Vec2::new(-r, 0.0)))
+        };
+        let diameter = segment_distance(point, [Vec2::new(-r, 0.0), Vec2::new(r, 0.0)]);
+        signed(arc.min(diameter), self.contains(point))
+    }
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let r = self.radius;
+        let arc = circle_hits_2d(origin, dir, r);
+        let arc = arc.into_iter().filter(|(_, normal)| normal.y >= 0.0);
+        let diameter = segment_hit_2d(origin, dir, [Vec2::new(-r, 0.0), Vec2::new(r, 0.0)]);
+        nearest_2d(origin, dir, max_toi, arc.chain(diameter))
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("radius", self.radius)
+    }
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let point = sample::in_disc(rng) * self.radius;
+        Vec2::new(point.x, point.y.abs())
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let r = self.radius;
+        match sample::pick(&[PI * r, 2.0 * r], rng) {
+            0 => {
+                let point = sample::on_circle(rng) * r;
+                Vec2::new(point.x, point.y.abs())
+            }
+            _ => Vec2::X * sample::symmetric(r, rng),
+        }
+    }
+}
+impl Shape2dBase for QuarterDisc {
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        let arc = circle(Vec2::X * self.radius, density).take(density + 1);
+        vec![iter::once(Vec2::ZERO)
+            .chain(arc)
+            .chain(iter::once(Vec2::ZERO))
+            .collect()]
+    }
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        fan(self.boundary(density))
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        if direction.x > 0.0 && direction.y > 0.0 {
+            direction.normalize() * self.radius
+        } else {
+            let r = self.radius;
+            max_dot([Vec2::ZERO, Vec2::X * r, Vec2::Y * r], direction)
+        }
+    }
+    fn area(&self) -> f32 {
+        PI * self.radius * self.radius / 4.0
+    }
+    fn perimeter(&self) -> f32 {
+        (PI / 2.0 + 2.0) * self.radius
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        let in_quadrant = point.x >= 0.0 && point.y >= 0.0;
+        in_quadrant && point.length_squared() <= self.radius * self.radius
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        let (x, y) = (Vec2::X * self.radius, Vec2::Y * self.radius);
+        let arc = if point.x >= 0.0 && point.y >= 0.0 {
+            (point.length() - self.radius).abs()
+        } else {
+            point.distance(x).min(point.distance(y))
+        };
+        let sides = segment_distance(point, [Vec2::ZERO, x]);
+        let sides = sides.min(segment_distance(point, [Vec2::ZERO, y]));
+        signed(arc.min(sides), self.contains(point))
+    }
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let (x, y) = (Vec2::X * self.radius, Vec2::Y * self.radius);
+        let arc = circle_hits_2d(origin, dir, self.radius);
+        let arc = arc.into_iter().filter(|(_, n)| n.x >= 0.0 && n.y >= 0.0);
+        let sides = [[Vec2::ZERO, x], [Vec2::ZERO, y]];
+        let sides = sides
+            .into_iter()
+            .filter_map(|side| segment_hit_2d(origin, dir, side));
+        nearest_2d(origin, dir, max_toi, arc.chain(sides))
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("radius", self.radius)
+    }
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        (sample::in_disc(rng) * self.radius).abs()
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let r = self.radius;
+        match sample::pick(&[PI / 2.0 * r, r, r], rng) {
+            0 => (sample::on_circle(rng) * r).abs(),
+            1 => Vec2::X * rng.gen::<f32>() * r,
+            _ => Vec2::Y * rng.gen::<f32>() * r,
+        }
+    }
+}
+impl Shape2dBase for Annulus {
+    /// The outer circle, and the inner one if the hole is not empty.
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        let Annulus { inner_radius, outer_radius } = *self;
+        let ring = |r: f32| {
+            circle(-Vec2::X * r, density)
+                .take(density * 4 + 1)
+                .collect()
+        };
+        if inner_radius == 0.0 {
+            vec![ring(outer_radius)]
+        } else {
+            vec![ring(outer_radius), ring(inner_radius)]
+        }
+    }
+    /// Quads between the inner and outer circles.
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        let Annulus { inner_radius, outer_radius } = *self;
+        let ring = |r: f32| circle(-Vec2::X * r, density).take(density * 4 + 1);
+        let (outer, inner): (Vec<_>, Vec<_>) = ring(outer_radius).zip(ring(inner_radius)).unzip();
+        let quads = outer.windows(2).zip(inner.windows(2));
+        let quad = |(o, i): (&[Vec2], &[Vec2])| [[o[0], o[1], i[0]], [i[0], o[1], i[1]]];
+        quads.flat_map(quad).collect()
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        direction.normalize_or_zero() * self.outer_radius
+    }
+    fn area(&self) -> f32 {
+        PI * (self.outer_radius.powi(2) - self.inner_radius.powi(2))
+    }
+    fn perimeter(&self) -> f32 {
+        TAU * (self.outer_radius + self.inner_radius)
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        let distance = point.length_squared();
+        let Annulus { inner_radius, outer_radius } = *self;
+        inner_radius * inner_radius <= distance && distance <= outer_radius * outer_radius
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        let Annulus { inner_radius, outer_radius } = *self;
+        let middle = (inner_radius + outer_radius) / 2.0;
+        (point.length() - middle).abs() - (outer_radius - inner_radius) / 2.0
+    }
+    /// Rays starting in the hole hit the inner circle from the inside.
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let outer = circle_hits_2d(origin, dir, self.outer_radius);
+        let inner = circle_hits_2d(origin, dir, self.inner_radius);
+        let inner = inner.into_iter().map(|(toi, normal)| (toi, -normal));
+        nearest_2d(origin, dir, max_toi, outer.into_iter().chain(inner))
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("inner_radius", self.inner_radius)?;
+        check_dimension("outer_radius", self.outer_radius)?;
+        check_inner_radius(self.inner_radius, self.outer_radius)
+    }
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        // Like `sample::in_disc`, the square of the distance to the center
+        // is uniform.
+        let (inner, outer) = (self.inner_radius.powi(2), self.outer_radius.powi(2));
+        let radius = (inner + rng.gen::<f32>() * (outer - inner)).sqrt();
+        sample::on_circle(rng) * radius
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let Annulus { inner_radius, outer_radius } = *self;
+        let radius = match sample::pick(&[outer_radius, inner_radius], rng) {
+            0 => outer_radius,
+            _ => inner_radius,
+        };
+        sample::on_circle(rng) * radius
+    }
+}
+impl Shape2dBase for Ellipse {
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        let unit = circle(-Vec2::X, density).take(density * 4 + 1);
+        vec![unit.map(|v| v * self.half_axes).collect()]
+    }
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        fan(self.boundary(density))
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.half_axes * (self.half_axes * direction).normalize_or_zero()
+    }
+    fn area(&self) -> f32 {
+        PI * self.half_axes.x * self.half_axes.y
+    }
+    /// There is no closed form for the perimeter of an ellipse, this uses
+    /// Ramanujan's second approximation.
+    fn perimeter(&self) -> f32 {
+        let [a, b] = self.half_axes.to_array();
+        if a + b == 0.0 {
+            return 0.0;
+        }
+        let h = ((a - b) / (a + b)).powi(2);
+        PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()))
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        (point / self.half_axes).length_squared() <= 1.0
+    }
+    /// The distance is approximated, it is exact on the boundary and when
+    /// both `half_axes` are equal.
+    fn sdf(&self, point: Vec2) -> f32 {
+        let axes = self.half_axes;
+        let scaled = (point / axes).length();
+        ellipsoid_sdf(scaled, (point / (axes * axes)).length(), axes.min_element())
+    }
+    /// The ray is scaled down with the ellipse to hit a unit circle.
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let axes = self.half_axes;
+        let hits = circle_hits_2d(origin / axes, dir / axes, 1.0);
+        let hits = hits
+            .into_iter()
+            .map(|(toi, normal)| (toi, (normal / axes).normalize()));
+        nearest_2d(origin, dir, max_toi, hits)
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_half_axes(&self.half_axes.to_array())
+    }
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        sample::in_disc(rng) * self.half_axes
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let [a, b] = self.half_axes.to_array();
+        // Stretching the unit circle scales its length by `stretch.length()`
+        // at `point`, so points are kept with a probability proportional to it.
+        loop {
+            let point = sample::on_circle(rng);
+            let stretch = Vec2::new(a * point.y, b * point.x);
+            if rng.gen::<f32>() * a.max(b) <= stretch.length() {
+                return point * self.half_axes;
+            }
+        }
+    }
+}
+impl Shape2dBase for Rectangle {
+    fn boundary(&self, _: usize) -> Vec<Vec<Vec2>> {
+        let a = self.size / 2.0;
+        let b = a * Vec2::new(1.0, -1.0);
+        vec![vec![a, b, -a, -b, a]]
+    }
+    fn triangulate(&self, _: usize) -> Vec<[Vec2; 3]> {
+        let a = self.size / 2.0;
+        let b = a * Vec2::new(1.0, -1.0);
+        vec![[a, b, -a], [-a, -b, a]]
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        let sign = |v: f32| if v >= 0.0 { 1.0 } else { -1.0 };
+        self.size / 2.0 * Vec2::new(sign(direction.x), sign(direction.y))
+    }
+    fn area(&self) -> f32 {
+        self.size.x * self.size.y
+    }
+    fn perimeter(&self) -> f32 {
+        2.0 * (self.size.x + self.size.y)
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        let half_size = self.size / 2.0;
+        point.abs().cmple(half_size).all()
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        let d = point.abs() - self.size / 2.0;
+        d.max(Vec2::ZERO).length() + d.max_element().min(0.0)
+    }
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let half_size = self.size / 2.0;
+        // See `Cuboid::cast_ray`.
+        let parallel = dir.cmpeq(Vec2::ZERO);
+        if (parallel & origin.abs().cmpgt(half_size)).any() {
+            return None;
+        }
+        let (near, far) = ((-half_size - origin) / dir, (half_size - origin) / dir);
+        let (enter, exit) = (near.min(far), near.max(far));
+        let enter = Vec2::select(parallel, Vec2::splat(f32::NEG_INFINITY), enter);
+        let exit = Vec2::select(parallel, Vec2::splat(f32::INFINITY), exit);
+        let toi = enter.max_element();
+        if toi > exit.min_element() {
+            return None;
+        }
+        let normal = if enter.x >= enter.y {
+            Vec2::new(-dir.x.signum(), 0.0)
+        } else {
+            Vec2::new(0.0, -dir.y.signum())
+        };
+        nearest_2d(origin, dir, max_toi, [(toi, normal)])
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("size", self.size.x)?;
+        check_dimension("size", self.size.y)
+    }
+}
+impl Shape2dBase for RoundedRectangle {
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        let inner = self.size / 2.0 - self.bevel;
+        // The start of each corner arc, with the direction of its center.
+        let corners = [
+            (Vec2::X, Vec2::ONE),
+            (Vec2::Y, Vec2::new(-1.0, 1.0)),
+            (-Vec2::X, -Vec2::ONE),
+            (-Vec2::Y, Vec2::new(1.0, -1.0)),
+        ];
+        let corners = corners.map(|(start, center)| {
+            let arc = circle(start * self.bevel, density).take(density + 1);
+            arc.map(move |v| v + inner * center)
+        });
+        let mut loop_: Vec<_> = corners.into_iter().flatten().collect();
+        // With a `bevel` of 0, all points of a corner are the same.
+        loop_.dedup();
+        loop_.push(loop_[0]);
+        vec![loop_]
+    }
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        fan(self.boundary(density))
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        let inner = self.size / 2.0 - self.bevel;
+        let corner = inner * Vec2::new(sign(direction.x), sign(direction.y));
+        corner + direction.normalize_or_zero() * self.bevel
+    }
+    fn area(&self) -> f32 {
+        let bevel = self.bevel;
+        self.size.x * self.size.y - (4.0 - PI) * bevel * bevel
+    }
+    fn perimeter(&self) -> f32 {
+        2.0 * (self.size.x + self.size.y) - (8.0 - TAU) * self.bevel
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        let outside_inner = (point.abs() - (self.size / 2.0 - self.bevel)).max(Vec2::ZERO);
+        outside_inner.length_squared() <= self.bevel * self.bevel
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        let d = point.abs() - (self.size / 2.0 - self.bevel);
+        d.max(Vec2::ZERO).length() + d.max_element().min(0.0) - self.bevel
+    }
+    /// The shape is the union of two rectangles and four discs, the first
+    /// hit is the first hit on any of them.
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let RoundedRectangle { size, bevel } = *self;
+        let inner = size / 2.0 - bevel;
+        let wide = Rectangle { size: size - Vec2::Y * 2.0 * bevel };
+        let tall = Rectangle { size: size - Vec2::X * 2.0 * bevel };
+        let rectangles = [wide, tall].map(|r| r.cast_ray(origin, dir, max_toi));
+        let corner = Disc { radius: bevel };
+        let centers = [
+            Vec2::ONE,
+            Vec2::new(-1.0, 1.0),
+            -Vec2::ONE,
+            Vec2::new(1.0, -1.0),
+        ];
+        let corners = centers.map(|center| corner.cast_ray(origin - inner * center, dir, max_toi));
+        let hits = rectangles.into_iter().chain(corners).flatten();
+        let hits = hits.map(|hit| (hit.toi, hit.normal));
+        nearest_2d(origin, dir, max_toi, hits)
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("size", self.size.x)?;
+        check_dimension("size", self.size.y)?;
+        check_bevel(self.bevel, self.size.min_element())
+    }
+    /// The rectangle is split into the inner rectangle, the 4 sides and the 4
+    /// corners, which are sampled according to their area.
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let bevel = self.bevel;
+        let inner = self.size / 2.0 - bevel;
+        let [x, y] = (inner * 2.0).to_array();
+        let weights = [x * y, 2.0 * bevel * y, 2.0 * bevel * x, PI * bevel * bevel];
+        let (axes, offset) = match sample::pick(&weights, rng) {
+            0 => (BVec2::new(false, false), Vec2::ZERO),
+            1 => (
+                BVec2::new(true, false),
+                Vec2::X * sample::symmetric(bevel, rng),
+            ),
+            2 => (
+                BVec2::new(false, true),
+                Vec2::Y * sample::symmetric(bevel, rng),
+            ),
+            _ => (BVec2::new(true, true), sample::in_disc(rng) * bevel),
+        };
+        sample::rounded_2d(inner, axes, offset, rng)
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        let bevel = self.bevel;
+        let inner = self.size / 2.0 - bevel;
+        let [x, y] = (inner * 2.0).to_array();
+        // With a `bevel` of 0, the sign of the zero still picks the side.
+        let side = sample::random_sign(rng) * bevel;
+        let (axes, offset) = match sample::pick(&[2.0 * y, 2.0 * x, TAU * bevel], rng) {
+            0 => (BVec2::new(true, false), Vec2::new(side, 0.0)),
+            1 => (BVec2::new(false, true), Vec2::new(0.0, side)),
+            _ => (BVec2::new(true, true), sample::on_circle(rng) * bevel),
+        };
+        sample::rounded_2d(inner, axes, offset, rng)
+    }
+}
+impl Shape2dBase for Triangle {
+    fn boundary(&self, _: usize) -> Vec<Vec<Vec2>> {
+        let Triangle { a, b, c } = *self;
+        vec![vec![a, b, c, a]]
+    }
+    fn triangulate(&self, _: usize) -> Vec<[Vec2; 3]> {
+        let Triangle { a, b, c } = *self;
+        vec![[a, b, c]]
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        max_dot([self.a, self.b, self.c], direction)
+    }
+    fn area(&self) -> f32 {
+        triangle_area([self.a, self.b, self.c])
+    }
+    fn perimeter(&self) -> f32 {
+        let Triangle { a, b, c } = *self;
+        a.distance(b) + b.distance(c) + c.distance(a)
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        triangle_contains([self.a, self.b, self.c], point)
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        let Triangle { a, b, c } = *self;
+        let edges = [[a, b], [b, c], [c, a]];
+        let distance = edges.map(|edge| segment_distance(point, edge));
+        let distance = distance.into_iter().fold(f32::INFINITY, f32::min);
+        signed(distance, self.contains(point))
+    }
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let Triangle { a, b, c } = *self;
+        let edges = [[a, b], [b, c], [c, a]];
+        let hits = edges
+            .into_iter()
+            .filter_map(|edge| segment_hit_2d(origin, dir, edge));
+        nearest_2d(origin, dir, max_toi, hits)
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_points(&[self.a, self.b, self.c], Vec2::is_finite)
+    }
+}
+impl Shape2dBase for Polygon {
+    /// Each of `lines` as its own strip.
+    fn boundary(&self, _: usize) -> Vec<Vec<Vec2>> {
+        let points = &self.points;
+        let segment = |[a, b]: &[usize; 2]| vec![points[*a], points[*b]];
+        self.lines.iter().map(segment).collect()
+    }
+    fn triangulate(&self, _: usize) -> Vec<[Vec2; 3]> {
+        let points = &self.points;
+        let tri = |[a, b, c]: &[usize; 3]| [points[*a], points[*b], points[*c]];
+        self.tris.iter().map(tri).collect()
+    }
+    /// Note that this is the support point of the convex hull of `points`.
+    fn support(&self, direction: Vec2) -> Vec2 {
+        max_dot(self.points.iter().copied(), direction)
+    }
+    /// The area of `tris`, this is 0 if the polygon is not triangulated.
+    fn area(&self) -> f32 {
+        self.triangulate(1).into_iter().map(triangle_area).sum()
+    }
+    /// The total length of `lines`.
+    fn perimeter(&self) -> f32 {
+        let points = &self.points;
+        let length = |[a, b]: &[usize; 2]| points[*a].distance(points[*b]);
+        self.lines.iter().map(length).sum()
+    }
+    /// Whether `point` is in one of `tris`.
+    ///
+    /// If the polygon is not triangulated, use the even-odd rule on `lines`
+    /// instead.
+    fn contains(&self, point: Vec2) -> bool {
+        if !self.tris.is_empty() {
+            let mut tris = self.triangulate(1).into_iter();
+            return tris.any(|tri| triangle_contains(tri, point));
+        }
+        let points = &self.points;
+        // Count how many lines a ray going toward +x crosses.
+        let crosses = |[a, b]: &&[usize; 2]| {
+            let (a, b) = (points[*a], points[*b]);
+            let straddles = (a.y > point.y) != (b.y > point.y);
+            straddles && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        };
+        self.lines.iter().filter(crosses).count() % 2 == 1
+    }
+    /// Distance to the closest of `lines`, negative inside the polygon as
+    /// defined by `contains`.
+    fn sdf(&self, point: Vec2) -> f32 {
+        let points = &self.points;
+        let distance = |[a, b]: &[usize; 2]| segment_distance(point, [points[*a], points[*b]]);
+        let distance = self
+            .lines
+            .iter()
+            .map(distance)
+            .fold(f32::INFINITY, f32::min);
+        signed(distance, self.contains(point))
+    }
+    /// Hits on `lines`, rays starting inside the polygon as defined by
+    /// `contains` hit at their origin.
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        if self.contains(origin) {
+            return inside_hit_2d(origin);
+        }
+        let points = &self.points;
+        let hit = |[a, b]: &[usize; 2]| segment_hit_2d(origin, dir, [points[*a], points[*b]]);
+        nearest_2d(origin, dir, max_toi, self.lines.iter().filter_map(hit))
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        check_points(&self.points, Vec2::is_finite)?;
+        let len = self.points.len();
+        check_indices(self.lines.iter().flatten(), len)?;
+        check_indices(self.tris.iter().flatten(), len)
+    }
+}
+
+impl Shape2d {
+    fn as_base(&self) -> &dyn Shape2dBase {
+        match self {
+            Shape2d::Disc(shape) => shape,
+            Shape2d::HalfDisc(shape) => shape,
+            Shape2d::QuarterDisc(shape) => shape,
+            Shape2d::Annulus(shape) => shape,
+            Shape2d::Ellipse(shape) => shape,
+            Shape2d::Rectangle(shape) => shape,
+            Shape2d::RoundedRectangle(shape) => shape,
+            Shape2d::Triangle(shape) => shape,
+            Shape2d::Polygon(shape) => shape,
+        }
+    }
+}
+impl Shape2dBase for Shape2d {
+    fn boundary(&self, density: usize) -> Vec<Vec<Vec2>> {
+        self.as_base().boundary(density)
+    }
+    fn triangulate(&self, density: usize) -> Vec<[Vec2; 3]> {
+        self.as_base().triangulate(density)
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.as_base().support(direction)
+    }
+    fn area(&self) -> f32 {
+        self.as_base().area()
+    }
+    fn perimeter(&self) -> f32 {
+        self.as_base().perimeter()
+    }
+    fn contains(&self, point: Vec2) -> bool {
+        self.as_base().contains(point)
+    }
+    fn sdf(&self, point: Vec2) -> f32 {
+        self.as_base().sdf(point)
+    }
+    fn cast_ray(&self, origin: Vec2, dir: Vec2, max_toi: f32) -> Option<RayHit2d> {
+        self.as_base().cast_ray(origin, dir, max_toi)
+    }
+    fn validate(&self) -> Result<(), ShapeError> {
+        self.as_base().validate()
+    }
+    #[cfg(feature = "rand")]
+    fn sample_interior(&self, rng: &mut dyn RngCore) -> Vec2 {
+        self.as_base().sample_interior(rng)
+    }
+    #[cfg(feature = "rand")]
+    fn sample_boundary(&self, rng: &mut dyn RngCore) -> Vec2 {
+        self.as_base().sample_boundary(rng)
+    }
+}
@@ -0,0 +1,363 @@
+//! Checking that shapes are well-formed.
+//!
+//! Shapes are plain structs with public fields, so nothing prevents building
+//! a malformed one. Use `validate` before handing user input to the other
+//! methods of a shape, or build them with `try_new`. 2d shapes implement it
+//! with [`Shape2dBase::validate`].
+
+use std::error::Error;
+use std::fmt;
+
+use bevy_math::{Vec2, Vec3};
+
+use crate::*;
+
+/// Why a shape is malformed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    /// A radius, height or size is negative or not finite.
+    InvalidDimension { name: &'static str, value: f32 },
+    /// A dimension the shape divides by is zero, such as the `half_axes` of
+    /// an [`Ellipsoid`].
+    ZeroDimension { name: &'static str },
+    /// A point has a coordinate that is not finite.
+    NonFinitePoint { index: usize },
+    /// An index in `lines` or `tris` does not point to a point of the shape.
+    IndexOutOfRange { index: usize, len: usize },
+    /// The `bevel` of a rounded shape is more than half its smallest side.
+    BevelTooLarge { bevel: f32, max: f32 },
+    /// The `inner_radius` of an [`Annulus`] is larger than its
+    /// `outer_radius`.
+    InnerRadiusTooLarge {
+        inner_radius: f32,
+        outer_radius: f32,
+    },
+    /// A [`HeightField`] has less than 2 rows or 2 columns.
+    HeightFieldTooSmall { rows: usize, columns: usize },
+    /// A [`HeightField`] row has a different length than the first one.
+    RaggedHeightField {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// A [`HeightField`] height is not finite.
+    NonFiniteHeight { row: usize, column: usize },
+}
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShapeError::InvalidDimension { name, value } => {
+                write!(f, "`{name}` must be non-negative and finite, got {value}")
+            }
+            ShapeError::ZeroDimension { name } => write!(f, "`{name}` must not be zero"),
+            ShapeError::NonFinitePoint { index } => {
+                write!(f, "point {index} has a coordinate that is not finite")
+            }
+            ShapeError::IndexOutOfRange { index, len } => {
+                write!(f, "index {index} is out of range for {len} points")
+            }
+            ShapeError::BevelTooLarge { bevel, max } => {
+                write!(f, "`bevel` must be at most {max}, got {bevel}")
+            }
+            ShapeError::InnerRadiusTooLarge { inner_radius, outer_radius } => write!(
+                f,
+                "`inner_radius` must be at most `outer_radius` {outer_radius}, got {inner_radius}"
+            ),
+            ShapeError::HeightFieldTooSmall { rows, columns } => write!(
+                f,
+                "height field needs at least 2x2 heights, got {rows}x{columns}"
+            ),
+            ShapeError::RaggedHeightField { row, len, expected } => write!(
+                f,
+                "height field row {row} has {len} heights, expected {expected}"
+            ),
+            ShapeError::NonFiniteHeight { row, column } => write!(
+                f,
+                "height field height at row {row}, column {column} is not finite"
+            ),
+        }
+    }
+}
+impl Error for ShapeError {}
+
+/// Check that `value` is non-negative and finite.
+pub(crate) fn check_dimension(name: &'static str, value: f32) -> Result<(), ShapeError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(ShapeError::InvalidDimension { name, value })
+    }
+}
+fn check_size(name: &'static str, value: Vec3) -> Result<(), ShapeError> {
+    value
+        .to_array()
+        .into_iter()
+        .try_for_each(|v| check_dimension(name, v))
+}
+/// Check that `half_axes` are positive and finite, ellipses divide by them.
+pub(crate) fn check_half_axes(half_axes: &[f32]) -> Result<(), ShapeError> {
+    half_axes.iter().try_for_each(|value| {
+        check_dimension("half_axes", *value)?;
+        if *value == 0.0 {
+            Err(ShapeError::ZeroDimension { name: "half_axes" })
+        } else {
+            Ok(())
+        }
+    })
+}
+/// Check that `bevel` is valid for a shape which smallest side is
+/// `smallest_side`.
+pub(crate) fn check_bevel(bevel: f32, smallest_side: f32) -> Result<(), ShapeError> {
+    check_dimension("bevel", bevel)?;
+    let max = smallest_side / 2.0;
+    if bevel <= max {
+        Ok(())
+    } else {
+        Err(ShapeError::BevelTooLarge { bevel, max })
+    }
+}
+/// Check that the hole of an [`Annulus`] fits in it.
+pub(crate) fn check_inner_radius(inner_radius: f32, outer_radius: f32) -> Result<(), ShapeError> {
+    if inner_radius <= outer_radius {
+        Ok(())
+    } else {
+        Err(ShapeError::InnerRadiusTooLarge { inner_radius, outer_radius })
+    }
+}
+/// Check that all `points` are finite, `is_finite` being `Vec2::is_finite`
+/// or `Vec3::is_finite`.
+pub(crate) fn check_points<P: Copy>(
+    points: &[P],
+    is_finite: impl Fn(P) -> bool,
+) -> Result<(), ShapeError> {
+    match points.iter().position(|p| !is_finite(*p)) {
+        Some(index) => Err(ShapeError::NonFinitePoint { index }),
+        None => Ok(()),
+    }
+}
+/// Check that all `indices` point into a list of `len` points.
+pub(crate) fn check_indices<'a>(
+    indices: impl IntoIterator<Item = &'a usize>,
+    len: usize,
+) -> Result<(), ShapeError> {
+    match indices.into_iter().find(|index| **index >= len) {
+        Some(index) => Err(ShapeError::IndexOutOfRange { index: *index, len }),
+        None => Ok(()),
+    }
+}
+
+impl<B: Shape2dBase> Pyramid<B> {
+    pub fn try_new(base: B, height: f32) -> Result<Self, ShapeError> {
+        let shape = Pyramid { base, height };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        self.base.validate()?;
+        check_dimension("height", self.height)
+    }
+}
+impl<B: Shape2dBase> Extruded<B> {
+    pub fn try_new(base: B, height: f32) -> Result<Self, ShapeError> {
+        let shape = Extruded { base, height };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        self.base.validate()?;
+        check_dimension("height", self.height)
+    }
+}
+impl Sphere {
+    pub fn try_new(radius: f32) -> Result<Self, ShapeError> {
+        let shape = Sphere { radius };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("radius", self.radius)
+    }
+}
+impl HalfSphere {
+    pub fn try_new(radius: f32) -> Result<Self, ShapeError> {
+        let shape = HalfSphere { radius };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("radius", self.radius)
+    }
+}
+impl Capsule {
+    pub fn try_new(radius: f32, segment_height: f32) -> Result<Self, ShapeError> {
+        let shape = Capsule { radius, segment_height };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("radius", self.radius)?;
+        check_dimension("segment_height", self.segment_height)
+    }
+}
+impl Cuboid {
+    pub fn try_new(size: Vec3) -> Result<Self, ShapeError> {
+        let shape = Cuboid { size };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_size("size", self.size)
+    }
+}
+impl RoundedCuboid {
+    pub fn try_new(size: Vec3, bevel: f32) -> Result<Self, ShapeError> {
+        let shape = RoundedCuboid { size, bevel };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_size("size", self.size)?;
+        check_bevel(self.bevel, self.size.min_element())
+    }
+}
+impl Cone {
+    pub fn try_new(height: f32, base_radius: f32) -> Result<Self, ShapeError> {
+        let shape = Cone { height, base_radius };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("height", self.height)?;
+        check_dimension("base_radius", self.base_radius)
+    }
+}
+impl Tetrahedron {
+    pub fn try_new(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> Result<Self, ShapeError> {
+        let shape = Tetrahedron { a, b, c, d };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        let Tetrahedron { a, b, c, d } = *self;
+        check_points(&[a, b, c, d], Vec3::is_finite)
+    }
+}
+impl Lines {
+    pub fn try_new(points: Vec<Vec3>, lines: Vec<[usize; 2]>) -> Result<Self, ShapeError> {
+        let shape = Lines { points, lines };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_points(&self.points, Vec3::is_finite)?;
+        check_indices(self.lines.iter().flatten(), self.points.len())
+    }
+}
+impl Cylinder {
+    pub fn try_new(height: f32, radius: f32) -> Result<Self, ShapeError> {
+        let shape = Cylinder { height, radius };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("height", self.height)?;
+        check_dimension("radius", self.radius)
+    }
+}
+impl Torus {
+    pub fn try_new(major_radius: f32, minor_radius: f32) -> Result<Self, ShapeError> {
+        let shape = Torus { major_radius, minor_radius };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("major_radius", self.major_radius)?;
+        check_dimension("minor_radius", self.minor_radius)
+    }
+}
+impl Ellipsoid {
+    pub fn try_new(half_axes: Vec3) -> Result<Self, ShapeError> {
+        let shape = Ellipsoid { half_axes };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_half_axes(&self.half_axes.to_array())
+    }
+}
+impl HeightField {
+    pub fn try_new(heights: Vec<Vec<f32>>, size: Vec2) -> Result<Self, ShapeError> {
+        let shape = HeightField { heights, size };
+        shape.validate().map(|()| shape)
+    }
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        check_dimension("size", self.size.x)?;
+        check_dimension("size", self.size.y)?;
+        let rows = self.heights.len();
+        let columns = self.heights.first().map_or(0, Vec::len);
+        if rows < 2 || columns < 2 {
+            return Err(ShapeError::HeightFieldTooSmall { rows, columns });
+        }
+        let ragged = self.heights.iter().position(|row| row.len() != columns);
+        if let Some(row) = ragged {
+            let len = self.heights[row].len();
+            return Err(ShapeError::RaggedHeightField { row, len, expected: columns });
+        }
+        let non_finite = self.heights.iter().enumerate().find_map(|(row, heights)| {
+            let column = heights.iter().position(|height| !height.is_finite())?;
+            Some(ShapeError::NonFiniteHeight { row, column })
+        });
+        match non_finite {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Disc {
+    pub fn try_new(radius: f32) -> Result<Self, ShapeError> {
+        let shape = Disc { radius };
+        shape.validate().map(|()| shape)
+    }
+}
+impl HalfDisc {
+    pub fn try_new(radius: f32) -> Result<Self, ShapeError> {
+        let shape = HalfDisc { radius };
+        shape.validate().map(|()| shape)
+    }
+}
+impl QuarterDisc {
+    pub fn try_new(radius: f32) -> Result<Self, ShapeError> {
+        let shape = QuarterDisc { radius };
+        shape.validate().map(|()| shape)
+    }
+}
+impl Annulus {
+    pub fn try_new(inner_radius: f32, outer_radius: f32) -> Result<Self, ShapeError> {
+        let shape = Annulus { inner_radius, outer_radius };
+        shape.validate().map(|()| shape)
+    }
+}
+impl Ellipse {
+    pub fn try_new(half_axes: Vec2) -> Result<Self, ShapeError> {
+        let shape = Ellipse { half_axes };
+        shape.validate().map(|()| shape)
+    }
+}
+impl Rectangle {
+    pub fn try_new(size: Vec2) -> Result<Self, ShapeError> {
+        let shape = Rectangle { size };
+        shape.validate().map(|()| shape)
+    }
+}
+impl RoundedRectangle {
+    pub fn try_new(size: Vec2, bevel: f32) -> Result<Self, ShapeError> {
+        let shape = RoundedRectangle { size, bevel };
+        shape.validate().map(|()| shape)
+    }
+}
+impl Triangle {
+    pub fn try_new(a: Vec2, b: Vec2, c: Vec2) -> Result<Self, ShapeError> {
+        let shape = Triangle { a, b, c };
+        shape.validate().map(|()| shape)
+    }
+}
+impl Polygon {
+    /// A polygon from its parts, see [`Polygon::new`] to build `lines` and
+    /// `tris` from a boundary.
+    pub fn try_new(
+        points: Vec<Vec2>,
+        lines: Vec<[usize; 2]>,
+        tris: Vec<[usize; 3]>,
+    ) -> Result<Self, ShapeError> {
+        let shape = Polygon { points, lines, tris };
+        shape.validate().map(|()| shape)
+    }
+}
@@ -0,0 +1,155 @@
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+
+/// Check that `contains` is true for the `inside` points and the `boundary`
+/// points, and false for the `outside` points.
+fn check<T: Copy + std::fmt::Debug>(
+    contains: impl Fn(T) -> bool,
+    inside: &[T],
+    boundary: &[T],
+    outside: &[T],
+) {
+    for point in inside.iter().chain(boundary) {
+        assert!(contains(*point), "{point:?} should be contained");
+    }
+    for point in outside {
+        assert!(!contains(*point), "{point:?} should not be contained");
+    }
+}
+fn v2(x: f32, y: f32) -> Vec2 {
+    Vec2::new(x, y)
+}
+fn v3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+
+#[test]
+fn half_disc() {
+    let half = HalfDisc { radius: 2.0 };
+    check(
+        |p| half.contains(p),
+        &[v2(0.0, 1.0), v2(-1.0, 1.0), v2(1.9, 0.1)],
+        &[v2(0.0, 0.0), v2(2.0, 0.0), v2(-2.0, 0.0), v2(0.0, 2.0)],
+        &[v2(0.0, -0.1), v2(1.0, -1.0), v2(0.0, 2.1), v2(1.5, 1.5)],
+    );
+}
+
+#[test]
+fn quarter_disc() {
+    let quarter = QuarterDisc { radius: 2.0 };
+    check(
+        |p| quarter.contains(p),
+        &[v2(1.0, 1.0), v2(0.1, 1.9)],
+        &[v2(0.0, 0.0), v2(2.0, 0.0), v2(0.0, 2.0), v2(1.0, 0.0)],
+        &[v2(-0.1, 1.0), v2(1.0, -0.1), v2(-1.0, -1.0), v2(1.5, 1.5)],
+    );
+}
+
+#[test]
+fn half_sphere() {
+    let half = HalfSphere { radius: 2.0 };
+    check(
+        |p| half.contains(p),
+        &[v3(0.0, 1.0, 0.0), v3(1.0, 0.5, -1.0)],
+        &[
+            Vec3::ZERO,
+            v3(2.0, 0.0, 0.0),
+            v3(0.0, 2.0, 0.0),
+            v3(0.0, 0.0, -2.0),
+        ],
+        &[v3(0.0, -0.1, 0.0), v3(0.0, 2.1, 0.0), v3(1.5, 1.5, 0.0)],
+    );
+}
+
+#[test]
+fn capsule() {
+    let capsule = Capsule { radius: 1.0, segment_height: 2.0 };
+    check(
+        |p| capsule.contains(p),
+        &[Vec3::ZERO, v3(0.5, 1.5, 0.0), v3(0.0, -1.0, 0.9)],
+        &[
+            v3(1.0, 0.0, 0.0),
+            v3(0.0, 2.0, 0.0),
+            v3(0.0, -2.0, 0.0),
+            v3(0.0, 1.0, 1.0),
+        ],
+        &[v3(1.1, 0.0, 0.0), v3(0.0, 2.1, 0.0), v3(0.8, 1.8, 0.0)],
+    );
+}
+
+#[test]
+fn cone() {
+    // The apex is at the top, the base at the bottom.
+    let cone = Cone { height: 2.0, base_radius: 1.0 };
+    check(
+        |p| cone.contains(p),
+        &[Vec3::ZERO, v3(0.0, 0.9, 0.0), v3(0.9, -0.9, 0.0)],
+        &[
+            v3(0.0, 1.0, 0.0),
+            v3(1.0, -1.0, 0.0),
+            v3(0.0, -1.0, 0.0),
+            v3(0.5, 0.0, 0.0),
+        ],
+        &[
+            v3(0.0, 1.1, 0.0),
+            v3(0.0, -1.1, 0.0),
+            v3(0.6, 0.0, 0.0),
+            v3(0.0, 0.5, 0.5),
+        ],
+    );
+}
+
+#[test]
+fn tetrahedron() {
+    let tetrahedron = Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z };
+    check(
+        |p| tetrahedron.contains(p),
+        &[Vec3::splat(0.2), v3(0.1, 0.1, 0.7)],
+        &[Vec3::ZERO, Vec3::X, v3(0.5, 0.5, 0.0), v3(0.25, 0.25, 0.5)],
+        &[Vec3::splat(0.4), v3(-0.1, 0.2, 0.2), v3(0.2, 0.2, -0.1)],
+    );
+    // The order of the corners doesn't matter.
+    let reversed = Tetrahedron { a: Vec3::Z, b: Vec3::Y, c: Vec3::X, d: Vec3::ZERO };
+    assert!(reversed.contains(Vec3::splat(0.2)));
+    assert!(!reversed.contains(Vec3::splat(0.4)));
+}
+
+#[test]
+fn pyramid() {
+    let base = Shape2d::Rectangle(Rectangle { size: Vec2::splat(2.0) });
+    let pyramid = Pyramid { base, height: 2.0 };
+    check(
+        |p| pyramid.contains(p),
+        &[Vec3::ZERO, v3(0.9, -0.9, -0.9), v3(0.0, 0.9, 0.0)],
+        &[
+            v3(0.0, 1.0, 0.0),
+            v3(1.0, -1.0, 1.0),
+            v3(0.5, 0.0, 0.5),
+            v3(0.0, -1.0, 0.0),
+        ],
+        &[
+            v3(0.0, 1.1, 0.0),
+            v3(0.6, 0.0, 0.0),
+            v3(0.0, -1.1, 0.0),
+            v3(0.1, 1.0, 0.0),
+        ],
+    );
+    // The base is in the XZ plane.
+    let base = Shape2d::QuarterDisc(QuarterDisc { radius: 2.0 });
+    let pyramid = Pyramid { base, height: 2.0 };
+    assert!(pyramid.contains(v3(0.5, -0.5, 0.5)));
+    assert!(!pyramid.contains(v3(-0.5, -0.5, 0.5)));
+    assert!(!pyramid.contains(v3(0.5, -0.5, -0.5)));
+}
+
+#[test]
+fn extruded() {
+    let base = Shape2d::Annulus(Annulus { inner_radius: 1.0, outer_radius: 2.0 });
+    let extruded = Extruded { base, height: 2.0 };
+    check(
+        |p| extruded.contains(p),
+        &[v3(1.5, 0.0, 0.0), v3(0.0, 0.9, -1.5)],
+        &[v3(1.0, 0.0, 0.0), v3(0.0, 1.0, 2.0), v3(-1.5, -1.0, 0.0)],
+        &[Vec3::ZERO, v3(1.5, 1.1, 0.0), v3(0.0, 0.0, 2.1)],
+    );
+}
@@ -0,0 +1,118 @@
+use std::f32::consts::PI;
+
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+
+const EPSILON: f32 = 1e-4;
+
+fn check(found: f32, expected: f32) {
+    let error = (found - expected).abs() / expected.abs().max(1.0);
+    assert!(error < EPSILON, "{found} != {expected}");
+}
+
+#[test]
+fn areas_and_perimeters() {
+    let disc = Disc { radius: 2.0 };
+    check(disc.area(), 4.0 * PI);
+    check(disc.perimeter(), 4.0 * PI);
+    let half = HalfDisc { radius: 2.0 };
+    check(half.area(), 2.0 * PI);
+    check(half.perimeter(), 2.0 * PI + 4.0);
+    let quarter = QuarterDisc { radius: 2.0 };
+    check(quarter.area(), PI);
+    check(quarter.perimeter(), PI + 4.0);
+    let annulus = Annulus { inner_radius: 1.0, outer_radius: 2.0 };
+    check(annulus.area(), 3.0 * PI);
+    check(annulus.perimeter(), 6.0 * PI);
+    let rectangle = Rectangle { size: Vec2::new(2.0, 3.0) };
+    check(rectangle.area(), 6.0);
+    check(rectangle.perimeter(), 10.0);
+    let triangle = Triangle { a: Vec2::ZERO, b: Vec2::X * 3.0, c: Vec2::Y * 4.0 };
+    check(triangle.area(), 6.0);
+    check(triangle.perimeter(), 12.0);
+}
+
+#[test]
+fn round_shapes() {
+    let sphere = Sphere { radius: 2.0 };
+    check(sphere.volume(), 32.0 / 3.0 * PI);
+    check(sphere.surface_area(), 16.0 * PI);
+    let half = HalfSphere { radius: 2.0 };
+    check(half.volume(), 16.0 / 3.0 * PI);
+    // The dome and the flat disc closing it.
+    check(half.surface_area(), 8.0 * PI + 4.0 * PI);
+
+    // A sphere cut in two, with a cylinder in between.
+    let capsule = Capsule { radius: 2.0, segment_height: 3.0 };
+    let cylinder = Cylinder { radius: 2.0, height: 3.0 };
+    check(capsule.volume(), sphere.volume() + cylinder.volume());
+    check(capsule.surface_area(), 16.0 * PI + 12.0 * PI);
+    check(cylinder.volume(), 12.0 * PI);
+    check(cylinder.surface_area(), 8.0 * PI + 12.0 * PI);
+
+    // The slant height of this cone is 5.
+    let cone = Cone { height: 4.0, base_radius: 3.0 };
+    check(cone.volume(), 12.0 * PI);
+    check(cone.surface_area(), 9.0 * PI + 15.0 * PI);
+
+    // Pappus's theorems: the minor circle revolved around the Y axis.
+    let torus = Torus { major_radius: 3.0, minor_radius: 1.0 };
+    check(torus.volume(), PI * 2.0 * PI * 3.0);
+    check(torus.surface_area(), 2.0 * PI * 2.0 * PI * 3.0);
+
+    // A sphere is an ellipsoid with equal axes.
+    let ellipsoid = Ellipsoid { half_axes: Vec3::splat(2.0) };
+    check(ellipsoid.volume(), sphere.volume());
+    let error = (ellipsoid.surface_area() - sphere.surface_area()).abs();
+    assert!(error < 0.011 * sphere.surface_area());
+}
+
+#[test]
+fn flat_shapes() {
+    let cuboid = Cuboid { size: Vec3::new(1.0, 2.0, 3.0) };
+    check(cuboid.volume(), 6.0);
+    check(cuboid.surface_area(), 22.0);
+    let base = Rectangle { size: Vec2::new(1.0, 3.0) };
+    let extruded = Extruded { base, height: 2.0 };
+    check(extruded.volume(), cuboid.volume());
+    check(extruded.surface_area(), cuboid.surface_area());
+
+    // A square pyramid, its sides are 4 triangles of height 5.
+    let base = Rectangle { size: Vec2::splat(6.0) };
+    let pyramid = Pyramid { base, height: 4.0 };
+    check(pyramid.volume(), 48.0);
+    check(pyramid.surface_area(), 36.0 + 4.0 * 15.0);
+
+    let tetrahedron = Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z };
+    check(tetrahedron.volume(), 1.0 / 6.0);
+    check(tetrahedron.surface_area(), 1.5 + 3f32.sqrt() / 2.0);
+
+    // Without bevel, a rounded cuboid is a cuboid.
+    let rounded = RoundedCuboid { size: cuboid.size, bevel: 0.0 };
+    check(rounded.volume(), cuboid.volume());
+    check(rounded.surface_area(), cuboid.surface_area());
+    // With the largest bevel, it is a sphere.
+    let rounded = RoundedCuboid { size: Vec3::splat(4.0), bevel: 2.0 };
+    check(rounded.volume(), 32.0 / 3.0 * PI);
+    check(rounded.surface_area(), 16.0 * PI);
+
+    let lines = Lines {
+        points: vec![Vec3::ZERO, Vec3::new(3.0, 4.0, 0.0), Vec3::X],
+        lines: vec![[0, 1], [0, 2]],
+    };
+    check(lines.length(), 6.0);
+}
+
+#[test]
+fn height_field() {
+    let size = Vec2::new(2.0, 3.0);
+    let flat = HeightField { heights: vec![vec![1.0; 4]; 3], size };
+    check(flat.surface_area(), size.x * size.y);
+    check(flat.volume(), size.x * size.y);
+
+    // A slope rising by 1 over the X axis.
+    let heights = vec![vec![0.0; 2], vec![1.0; 2]];
+    let slope = HeightField { heights, size: Vec2::ONE };
+    check(slope.surface_area(), 2f32.sqrt());
+    check(slope.volume(), 0.5);
+}
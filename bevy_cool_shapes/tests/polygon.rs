@@ -0,0 +1,92 @@
+use bevy_cool_shapes::*;
+use bevy_math::Vec2;
+
+const EPSILON: f32 = 1e-5;
+
+fn loop_(points: &[[f32; 2]]) -> Vec<Vec2> {
+    points.iter().copied().map(Vec2::from).collect()
+}
+fn square(min: [f32; 2], max: [f32; 2]) -> Vec<Vec2> {
+    loop_(&[min, [max[0], min[1]], max, [min[0], max[1]]])
+}
+
+/// Check that `polygon` has `tris` counter-clockwise triangles, covering
+/// `area`.
+fn check(polygon: &Polygon, tris: usize, area: f32) {
+    assert_eq!(polygon.tris.len(), tris, "{polygon:?}");
+    let mut total = 0.0;
+    for tri in polygon.triangulate(1) {
+        let [a, b, c] = tri;
+        let signed_area = (b - a).perp_dot(c - a) / 2.0;
+        assert!(signed_area > 0.0, "{tri:?} is flat or clockwise");
+        total += signed_area;
+    }
+    assert!((total - area).abs() < EPSILON, "{total} != {area}");
+    assert!((polygon.area() - area).abs() < EPSILON);
+}
+
+#[test]
+fn concave() {
+    // An L, with a reflex corner at (1, 1).
+    let l = loop_(&[[0., 0.], [2., 0.], [2., 1.], [1., 1.], [1., 2.], [0., 2.]]);
+    let polygon = Polygon::new(l.clone());
+    check(&polygon, 6 - 2, 3.0);
+    assert!(!polygon.contains(Vec2::new(1.5, 1.5)));
+    assert!(polygon.contains(Vec2::new(0.5, 1.5)));
+
+    // Clockwise, and closed by repeating the first point.
+    let clockwise = l.iter().rev().chain(l.last()).copied();
+    let polygon = Polygon::new(clockwise);
+    check(&polygon, 6 - 2, 3.0);
+    assert_eq!(polygon.points.len(), 6);
+}
+
+#[test]
+fn one_hole() {
+    let outer = square([0., 0.], [4., 4.]);
+    let hole = square([1., 1.5], [2., 2.5]);
+    // Each hole adds a bridge, going back and forth between two vertices.
+    let polygon = Polygon::with_holes(outer.clone(), [hole.clone()]);
+    check(&polygon, 8 - 2 + 2, 15.0);
+    assert!(!polygon.contains(Vec2::new(1.5, 2.0)));
+    assert!(polygon.contains(Vec2::new(3.0, 3.0)));
+
+    // The winding of the hole doesn't matter.
+    let reversed = hole.into_iter().rev();
+    let polygon = Polygon::with_holes(outer, [reversed]);
+    check(&polygon, 8 - 2 + 2, 15.0);
+}
+
+#[test]
+fn two_holes() {
+    let outer = square([0., 0.], [4., 4.]);
+    let holes = [square([0.5, 0.5], [1.5, 1.5]), square([2.5, 1.], [3.5, 3.])];
+    let polygon = Polygon::with_holes(outer, holes);
+    check(&polygon, 12 - 2 + 2 * 2, 13.0);
+    assert!(!polygon.contains(Vec2::new(1.0, 1.0)));
+    assert!(!polygon.contains(Vec2::new(3.0, 2.0)));
+    assert!(polygon.contains(Vec2::new(2.0, 2.0)));
+}
+
+#[test]
+fn degenerate_holes() {
+    let outer = square([0., 0.], [4., 4.]);
+    let holes = [
+        loop_(&[[1., 1.], [2., 2.]]),
+        loop_(&[[1., 1.], [2., 2.], [3., 3.]]),
+        loop_(&[[1., 1.], [1., 1.], [1., 1.], [1., 1.]]),
+    ];
+    let polygon = Polygon::with_holes(outer, holes);
+    check(&polygon, 4 - 2, 16.0);
+    assert_eq!(polygon.points.len(), 4);
+    assert_eq!(polygon.lines.len(), 4);
+}
+
+#[test]
+fn collinear_points() {
+    // Flat corners don't add triangles.
+    let boundary = loop_(&[[0., 0.], [1., 0.], [2., 0.], [2., 2.], [1., 2.], [0., 2.]]);
+    let polygon = Polygon::new(boundary);
+    check(&polygon, 6 - 2 - 2, 4.0);
+    assert_eq!(polygon.lines.len(), 6);
+}
@@ -0,0 +1,188 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy_cool_shapes::query::{contact, intersects, Contact, SupportMap};
+use bevy_cool_shapes::*;
+use bevy_math::{Mat4, Quat, Vec2, Vec3};
+
+const EPSILON: f32 = 1e-3;
+
+fn at(x: f32, y: f32, z: f32) -> Mat4 {
+    Mat4::from_translation(Vec3::new(x, y, z))
+}
+
+/// Check that `b` at `b_transform` is `distance` away from `a` at the
+/// origin, and that [`intersects`] agrees.
+fn check_separated(a: &impl SupportMap, b: &impl SupportMap, b_transform: Mat4, distance: f32) {
+    let found = contact(a, Mat4::IDENTITY, b, b_transform);
+    match found {
+        Contact::Separated { distance: found_distance, point_a, point_b } => {
+            assert!((found_distance - distance).abs() < EPSILON, "{found:?}");
+            assert!(
+                (point_a.distance(point_b) - distance).abs() < EPSILON,
+                "{found:?}"
+            );
+        }
+        Contact::Penetrating { .. } => panic!("{found:?} should be separated"),
+    }
+    assert!(!intersects(a, Mat4::IDENTITY, b, b_transform));
+}
+/// Check that `b` at `b_transform` penetrates `a` at the origin by `depth`
+/// along `normal`, and that [`intersects`] agrees.
+fn check_penetrating(
+    a: &impl SupportMap,
+    b: &impl SupportMap,
+    b_transform: Mat4,
+    depth: f32,
+    normal: Vec3,
+) {
+    let found = contact(a, Mat4::IDENTITY, b, b_transform);
+    match found {
+        Contact::Penetrating {
+            depth: found_depth,
+            normal: found_normal,
+            point_a,
+            point_b,
+        } => {
+            assert!((found_depth - depth).abs() < EPSILON, "{found:?}");
+            // EPA approximates curved surfaces with a polytope, the normal
+            // is only close in direction.
+            assert!(found_normal.dot(normal) > 1.0 - EPSILON, "{found:?}");
+            // Moving `b` by `depth` along `normal` brings the points together.
+            let gap = (point_a - point_b).dot(normal);
+            assert!((gap - depth).abs() < EPSILON, "{found:?}");
+        }
+        Contact::Separated { .. } => panic!("{found:?} should be penetrating"),
+    }
+    assert!(intersects(a, Mat4::IDENTITY, b, b_transform));
+}
+
+#[test]
+fn sphere_sphere() {
+    let sphere = Sphere { radius: 1.0 };
+    check_separated(&sphere, &sphere, at(3.0, 0.0, 0.0), 1.0);
+    let touching = contact(&sphere, Mat4::IDENTITY, &sphere, at(0.0, 2.0, 0.0));
+    assert!(touching.distance().abs() < EPSILON, "{touching:?}");
+    check_penetrating(&sphere, &sphere, at(1.5, 0.0, 0.0), 0.5, Vec3::X);
+}
+
+#[test]
+fn cuboid_cuboid() {
+    let cuboid = Cuboid { size: Vec3::splat(2.0) };
+    check_separated(&cuboid, &cuboid, at(3.0, 0.5, 0.0), 1.0);
+    let touching = contact(&cuboid, Mat4::IDENTITY, &cuboid, at(0.0, 0.0, 2.0));
+    assert!(touching.distance().abs() < EPSILON, "{touching:?}");
+    check_penetrating(&cuboid, &cuboid, at(1.5, 0.2, 0.1), 0.5, Vec3::X);
+    check_penetrating(&cuboid, &cuboid, at(0.1, -1.75, 0.0), 0.25, -Vec3::Y);
+}
+
+#[test]
+fn separated_points() {
+    let sphere = Sphere { radius: 1.0 };
+    let cuboid = Cuboid { size: Vec3::splat(2.0) };
+    let found = contact(&sphere, Mat4::IDENTITY, &cuboid, at(0.0, 0.0, 4.0));
+    let expected = Contact::Separated {
+        distance: 2.0,
+        point_a: Vec3::Z,
+        point_b: Vec3::Z * 3.0,
+    };
+    match found {
+        Contact::Separated { distance, point_a, point_b } => {
+            assert!((distance - 2.0).abs() < EPSILON, "{found:?}");
+            assert!(
+                point_a.abs_diff_eq(Vec3::Z, EPSILON),
+                "{found:?} != {expected:?}"
+            );
+            assert!(
+                point_b.abs_diff_eq(Vec3::Z * 3.0, EPSILON),
+                "{found:?} != {expected:?}"
+            );
+        }
+        Contact::Penetrating { .. } => panic!("{found:?} should be separated"),
+    }
+}
+
+#[test]
+fn epa_depth_and_normal() {
+    let sphere = Sphere { radius: 1.0 };
+    let cuboid = Cuboid { size: Vec3::splat(2.0) };
+    // The sphere sinks 0.25 into the top face of the cuboid.
+    check_penetrating(&cuboid, &sphere, at(0.3, 1.75, -0.2), 0.25, Vec3::Y);
+    let capsule = Capsule { radius: 0.5, segment_height: 2.0 };
+    check_penetrating(&capsule, &capsule, at(0.0, 2.0, 0.8), 0.2, Vec3::Z);
+}
+
+#[test]
+fn normal_points_from_a_to_b() {
+    let sphere = Sphere { radius: 1.0 };
+    let cuboid = Cuboid { size: Vec3::splat(2.0) };
+    let a_to_b = contact(&sphere, Mat4::IDENTITY, &cuboid, at(1.5, 0.0, 0.0));
+    let b_to_a = contact(&cuboid, at(1.5, 0.0, 0.0), &sphere, Mat4::IDENTITY);
+    match (a_to_b, b_to_a) {
+        (
+            Contact::Penetrating { normal, depth, .. },
+            Contact::Penetrating { normal: swapped_normal, depth: swapped_depth, .. },
+        ) => {
+            assert!(normal.abs_diff_eq(Vec3::X, EPSILON), "{a_to_b:?}");
+            assert!(swapped_normal.abs_diff_eq(-Vec3::X, EPSILON), "{b_to_a:?}");
+            assert!((depth - swapped_depth).abs() < EPSILON);
+        }
+        _ => panic!("{a_to_b:?} and {b_to_a:?} should be penetrating"),
+    }
+}
+
+#[test]
+fn rotated_and_scaled() {
+    let cuboid = Cuboid { size: Vec3::splat(2.0) };
+    // Rotated a quarter, the closest edge of `b` is √2 from its center.
+    let rotation = Quat::from_rotation_y(FRAC_PI_4);
+    let x = 1.0 + 2f32.sqrt() + 0.5;
+    let rotated = Mat4::from_rotation_translation(rotation, Vec3::X * x);
+    check_separated(&cuboid, &cuboid, rotated, 0.5);
+    let rotated = Mat4::from_rotation_translation(rotation, Vec3::X * (x - 0.75));
+    check_penetrating(&cuboid, &cuboid, rotated, 0.25, Vec3::X);
+
+    // Stretched along X, the sphere reaches 2.0 from its center.
+    let sphere = Sphere { radius: 1.0 };
+    let scale = Vec3::new(2.0, 1.0, 1.0);
+    let scaled = Mat4::from_scale_rotation_translation(scale, Quat::IDENTITY, Vec3::X * 3.5);
+    check_separated(&sphere, &sphere, scaled, 0.5);
+    let scaled = Mat4::from_scale_rotation_translation(scale, Quat::IDENTITY, Vec3::X * 2.5);
+    check_penetrating(&sphere, &sphere, scaled, 0.5, Vec3::X);
+
+    // Both shapes moved together do not change their contact.
+    let moved = Mat4::from_rotation_translation(Quat::from_rotation_z(1.0), Vec3::ONE);
+    let found = contact(&cuboid, moved, &cuboid, moved * at(3.0, 0.0, 0.0));
+    assert!((found.distance() - 1.0).abs() < EPSILON, "{found:?}");
+}
+
+#[test]
+fn non_convex_shapes_use_their_hull() {
+    let small = Sphere { radius: 0.5 };
+    // The sphere is in the hole of the torus, only overlapping its hull.
+    let torus = Torus { major_radius: 2.0, minor_radius: 0.5 };
+    assert!(torus.sdf(Vec3::ZERO) > small.radius);
+    assert!(intersects(&torus, Mat4::IDENTITY, &small, Mat4::IDENTITY));
+    check_separated(&torus, &small, at(0.0, 2.0, 0.0), 1.0);
+
+    let annulus = Annulus { inner_radius: 1.0, outer_radius: 2.0 };
+    let tube = Extruded { base: Shape2d::Annulus(annulus), height: 1.0 };
+    assert!(tube.sdf(Vec3::ZERO) > small.radius);
+    assert!(intersects(&tube, Mat4::IDENTITY, &small, Mat4::IDENTITY));
+
+    let base = Polygon::new(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(2.0, 0.0),
+        Vec2::new(2.0, 2.0),
+        Vec2::new(1.8, 2.0),
+        Vec2::new(1.8, 0.2),
+        Vec2::new(0.0, 0.2),
+    ]);
+    let corner = Extruded { base, height: 1.0 };
+    assert!(!corner.contains(Vec3::new(1.0, 0.0, 1.0)));
+    assert!(intersects(
+        &corner,
+        Mat4::IDENTITY,
+        &small,
+        at(1.0, 0.0, 1.0)
+    ));
+}
@@ -0,0 +1,385 @@
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+
+const EPSILON: f32 = 1e-3;
+
+type Cast<'a> = &'a dyn Fn(Vec3, Vec3, f32) -> Option<RayHit>;
+type Cast2d<'a> = &'a dyn Fn(Vec2, Vec2, f32) -> Option<RayHit2d>;
+
+/// A ray from `origin` along `dir`, expected to hit at `toi` with `normal`.
+struct Hit<V> {
+    origin: V,
+    dir: V,
+    toi: f32,
+    normal: V,
+}
+fn hit(origin: Vec3, dir: Vec3, toi: f32, normal: Vec3) -> Hit<Vec3> {
+    Hit { origin, dir, toi, normal: normal.normalize() }
+}
+fn hit_2d(origin: Vec2, dir: Vec2, toi: f32, normal: Vec2) -> Hit<Vec2> {
+    Hit { origin, dir, toi, normal: normal.normalize() }
+}
+
+/// Check each of `hits`, that they are cut off by a `max_toi` just short of
+/// them, that the ray from `miss` misses and that a ray starting at `inside`
+/// hits at its origin.
+fn check(cast: Cast, hits: &[Hit<Vec3>], miss: (Vec3, Vec3), inside: Option<Vec3>) {
+    for Hit { origin, dir, toi, normal } in hits {
+        let found = cast(*origin, *dir, 100.0);
+        let found = found.unwrap_or_else(|| panic!("{origin} {dir} misses"));
+        assert!(
+            (found.toi - toi).abs() < EPSILON,
+            "{origin} {dir}: {found:?}"
+        );
+        assert!(
+            found.normal.abs_diff_eq(*normal, EPSILON),
+            "{origin} {dir}: {found:?}"
+        );
+        let point = *origin + *dir * found.toi;
+        assert!(found.point.abs_diff_eq(point, EPSILON), "{found:?}");
+        assert_eq!(
+            cast(*origin, *dir, toi - 0.01),
+            None,
+            "{origin} {dir} cut off"
+        );
+        assert!(
+            cast(*origin, *dir, toi + 0.01).is_some(),
+            "{origin} {dir} cut off"
+        );
+    }
+    let (origin, dir) = miss;
+    assert_eq!(cast(origin, dir, 100.0), None, "{origin} {dir} hits");
+    if let Some(inside) = inside {
+        let found = cast(inside, Vec3::X, 100.0);
+        let expected = RayHit { toi: 0.0, point: inside, normal: Vec3::ZERO };
+        assert_eq!(found, Some(expected));
+    }
+}
+fn check_2d(cast: Cast2d, hits: &[Hit<Vec2>], miss: (Vec2, Vec2), inside: Vec2) {
+    for Hit { origin, dir, toi, normal } in hits {
+        let found = cast(*origin, *dir, 100.0);
+        let found = found.unwrap_or_else(|| panic!("{origin} {dir} misses"));
+        assert!(
+            (found.toi - toi).abs() < EPSILON,
+            "{origin} {dir}: {found:?}"
+        );
+        assert!(
+            found.normal.abs_diff_eq(*normal, EPSILON),
+            "{origin} {dir}: {found:?}"
+        );
+        assert_eq!(
+            cast(*origin, *dir, toi - 0.01),
+            None,
+            "{origin} {dir} cut off"
+        );
+        assert!(
+            cast(*origin, *dir, toi + 0.01).is_some(),
+            "{origin} {dir} cut off"
+        );
+    }
+    let (origin, dir) = miss;
+    assert_eq!(cast(origin, dir, 100.0), None, "{origin} {dir} hits");
+    let found = cast(inside, Vec2::X, 100.0);
+    let expected = RayHit2d { toi: 0.0, point: inside, normal: Vec2::ZERO };
+    assert_eq!(found, Some(expected));
+}
+macro_rules! cast {
+    ($shape:expr) => {
+        &|origin, dir, max_toi| $shape.cast_ray(origin, dir, max_toi)
+    };
+}
+
+#[test]
+fn shapes_2d() {
+    let disc = Disc { radius: 1.0 };
+    let hits = [hit_2d(Vec2::new(-5.0, 0.0), Vec2::X, 4.0, -Vec2::X)];
+    check_2d(
+        cast!(disc),
+        &hits,
+        (Vec2::new(-5.0, 2.0), Vec2::X),
+        Vec2::ZERO,
+    );
+
+    let rectangle = Rectangle { size: Vec2::new(2.0, 4.0) };
+    let hits = [
+        hit_2d(Vec2::new(-5.0, 0.0), Vec2::X, 4.0, -Vec2::X),
+        hit_2d(Vec2::new(0.5, 5.0), -Vec2::Y, 3.0, Vec2::Y),
+    ];
+    let miss = (Vec2::new(-5.0, 3.0), Vec2::X);
+    check_2d(cast!(rectangle), &hits, miss, Vec2::ZERO);
+    // Along the line of the top edge.
+    let hits = [hit_2d(Vec2::new(-5.0, 2.0), Vec2::X, 4.0, -Vec2::X)];
+    let miss = (Vec2::new(-5.0, 2.5), Vec2::X);
+    check_2d(cast!(rectangle), &hits, miss, Vec2::ZERO);
+
+    let annulus = Annulus { inner_radius: 1.0, outer_radius: 2.0 };
+    let hits = [
+        hit_2d(Vec2::new(-5.0, 0.0), Vec2::X, 3.0, -Vec2::X),
+        // From the hole, the inner circle is hit.
+        hit_2d(Vec2::ZERO, Vec2::X, 1.0, -Vec2::X),
+    ];
+    let miss = (Vec2::new(-5.0, 3.0), Vec2::X);
+    check_2d(cast!(annulus), &hits, miss, Vec2::new(1.5, 0.0));
+
+    let ellipse = Ellipse { half_axes: Vec2::new(2.0, 1.0) };
+    let hits = [
+        hit_2d(Vec2::new(-5.0, 0.0), Vec2::X, 3.0, -Vec2::X),
+        hit_2d(Vec2::new(0.0, 5.0), -Vec2::Y, 4.0, Vec2::Y),
+    ];
+    let miss = (Vec2::new(-5.0, 1.5), Vec2::X);
+    check_2d(cast!(ellipse), &hits, miss, Vec2::ZERO);
+}
+
+#[test]
+fn pyramid() {
+    let base = Shape2d::Rectangle(Rectangle { size: Vec2::splat(2.0) });
+    let pyramid = Pyramid { base, height: 2.0 };
+    let hits = [
+        hit(Vec3::new(0.2, -5.0, 0.3), Vec3::Y, 4.0, -Vec3::Y),
+        // The side goes from (-1, -1) to the apex at (0, 1).
+        hit(
+            Vec3::new(-5.0, 0.0, 0.0),
+            Vec3::X,
+            4.5,
+            Vec3::new(-2.0, 1.0, 0.0),
+        ),
+    ];
+    let miss = (Vec3::new(3.0, -5.0, 0.0), Vec3::Y);
+    check(cast!(pyramid), &hits, miss, Some(Vec3::new(0.0, -0.5, 0.0)));
+}
+
+#[test]
+fn extruded() {
+    let base = Shape2d::Disc(Disc { radius: 1.0 });
+    let extruded = Extruded { base, height: 2.0 };
+    let hits = [
+        hit(Vec3::new(0.2, 5.0, 0.3), -Vec3::Y, 4.0, Vec3::Y),
+        hit(Vec3::new(0.2, -5.0, 0.3), Vec3::Y, 4.0, -Vec3::Y),
+    ];
+    let miss = (Vec3::new(2.0, 5.0, 0.0), -Vec3::Y);
+    check(cast!(extruded), &hits, miss, Some(Vec3::ZERO));
+}
+
+#[test]
+fn sphere() {
+    let sphere = Sphere { radius: 1.0 };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 4.0, -Vec3::X),
+        // `dir` does not need to be normalized.
+        hit(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y * 2.0, 2.0, Vec3::Y),
+    ];
+    let miss = (Vec3::new(-5.0, 2.0, 0.0), Vec3::X);
+    check(cast!(sphere), &hits, miss, Some(Vec3::ZERO));
+}
+
+#[test]
+fn half_sphere() {
+    let half_sphere = HalfSphere { radius: 1.0 };
+    let hits = [
+        hit(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, 4.0, Vec3::Y),
+        hit(Vec3::new(0.0, -5.0, 0.0), Vec3::Y, 5.0, -Vec3::Y),
+    ];
+    // Passes below the flat side.
+    let miss = (Vec3::new(-5.0, -0.5, 0.0), Vec3::X);
+    check(
+        cast!(half_sphere),
+        &hits,
+        miss,
+        Some(Vec3::new(0.0, 0.5, 0.0)),
+    );
+}
+
+#[test]
+fn capsule() {
+    let capsule = Capsule { radius: 0.5, segment_height: 2.0 };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 4.5, -Vec3::X),
+        hit(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, 3.5, Vec3::Y),
+        hit(Vec3::new(0.0, -5.0, 0.0), Vec3::Y, 3.5, -Vec3::Y),
+    ];
+    let miss = (Vec3::new(-5.0, 0.0, 1.0), Vec3::X);
+    check(cast!(capsule), &hits, miss, Some(Vec3::new(0.0, 1.2, 0.0)));
+}
+
+#[test]
+fn cuboid() {
+    let cuboid = Cuboid { size: Vec3::new(1.0, 2.0, 3.0) };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 4.5, -Vec3::X),
+        hit(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z, 3.5, Vec3::Z),
+        hit(Vec3::new(-5.0, -5.0, -4.5), Vec3::ONE, 4.5, -Vec3::X),
+    ];
+    let miss = (Vec3::new(-5.0, 2.0, 0.0), Vec3::X);
+    check(cast!(cuboid), &hits, miss, Some(Vec3::ZERO));
+}
+
+#[test]
+fn cuboid_parallel_to_faces() {
+    let cuboid = Cuboid { size: Vec3::new(1.0, 2.0, 3.0) };
+    // The rays start on the plane of the top face and never leave it.
+    let hits = [hit(Vec3::new(-5.0, 1.0, 0.0), Vec3::X, 4.5, -Vec3::X)];
+    let miss = (Vec3::new(-5.0, 1.0, 2.0), Vec3::X);
+    check(cast!(cuboid), &hits, miss, None);
+    let miss = (Vec3::new(-5.0, 1.0, 0.0), Vec3::Z);
+    check(cast!(cuboid), &[], miss, None);
+}
+
+#[test]
+fn rounded_cuboid() {
+    let rounded = RoundedCuboid { size: Vec3::splat(2.0), bevel: 0.5 };
+    let edge_distance = Vec2::splat(4.5).length() - 0.5;
+    let corner_distance = Vec3::splat(4.5).length() - 0.5;
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 4.0, -Vec3::X),
+        // Along the diagonals, toward the edge cylinders and corner spheres.
+        hit(
+            Vec3::new(-5.0, 0.0, -5.0),
+            Vec3::new(1.0, 0.0, 1.0).normalize(),
+            edge_distance,
+            Vec3::new(-1.0, 0.0, -1.0),
+        ),
+        hit(
+            Vec3::splat(-5.0),
+            Vec3::ONE.normalize(),
+            corner_distance,
+            -Vec3::ONE,
+        ),
+    ];
+    // The corners of a sharp cuboid would be hit.
+    let miss = (Vec3::new(-5.0, 0.9, 0.9), Vec3::X);
+    check(cast!(rounded), &hits, miss, Some(Vec3::new(0.9, 0.0, 0.0)));
+}
+
+#[test]
+fn cone() {
+    let cone = Cone { height: 2.0, base_radius: 1.0 };
+    let hits = [
+        hit(Vec3::new(0.0, -5.0, 0.0), Vec3::Y, 4.0, -Vec3::Y),
+        hit(
+            Vec3::new(-5.0, 0.0, 0.0),
+            Vec3::X,
+            4.5,
+            Vec3::new(-2.0, 1.0, 0.0),
+        ),
+    ];
+    let miss = (Vec3::new(2.0, -5.0, 0.0), Vec3::Y);
+    check(cast!(cone), &hits, miss, Some(Vec3::new(0.0, -0.5, 0.0)));
+}
+
+#[test]
+fn tetrahedron() {
+    let tetrahedron = Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.2, 0.2), Vec3::X, 5.0, -Vec3::X),
+        hit(Vec3::splat(5.0), -Vec3::ONE, 5.0 - 1.0 / 3.0, Vec3::ONE),
+    ];
+    let miss = (Vec3::new(-5.0, 1.0, 1.0), Vec3::X);
+    check(cast!(tetrahedron), &hits, miss, Some(Vec3::splat(0.1)));
+}
+
+#[test]
+fn tiny_tetrahedron() {
+    // The faces are small enough for an unscaled parallel check to miss them.
+    let s = 1e-4;
+    let tetrahedron = Tetrahedron {
+        a: Vec3::ZERO,
+        b: Vec3::X * s,
+        c: Vec3::Y * s,
+        d: Vec3::Z * s,
+    };
+    let hits = [hit(
+        Vec3::new(-1.0, 0.2 * s, 0.2 * s),
+        Vec3::X,
+        1.0,
+        -Vec3::X,
+    )];
+    let miss = (Vec3::new(-1.0, s, s), Vec3::X);
+    check(cast!(tetrahedron), &hits, miss, Some(Vec3::splat(0.1 * s)));
+}
+
+#[test]
+fn cylinder() {
+    let cylinder = Cylinder { height: 2.0, radius: 1.0 };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 4.0, -Vec3::X),
+        hit(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, 4.0, Vec3::Y),
+    ];
+    let miss = (Vec3::new(-5.0, 2.0, 0.0), Vec3::X);
+    check(cast!(cylinder), &hits, miss, Some(Vec3::ZERO));
+}
+
+#[test]
+fn torus() {
+    let torus = Torus { major_radius: 2.0, minor_radius: 0.5 };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 2.5, -Vec3::X),
+        hit(Vec3::new(2.0, 5.0, 0.0), -Vec3::Y, 4.5, Vec3::Y),
+        // From the hole, the inside of the ring is hit.
+        hit(Vec3::ZERO, Vec3::Z, 1.5, -Vec3::Z),
+    ];
+    // Through the hole.
+    let miss = (Vec3::new(0.0, 5.0, 0.0), -Vec3::Y);
+    check(cast!(torus), &hits, miss, Some(Vec3::new(0.0, 0.0, 2.2)));
+}
+
+#[test]
+fn ellipsoid() {
+    let ellipsoid = Ellipsoid { half_axes: Vec3::new(1.0, 2.0, 0.5) };
+    let hits = [
+        hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, 4.0, -Vec3::X),
+        hit(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, 3.0, Vec3::Y),
+        hit(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z, 4.5, Vec3::Z),
+    ];
+    let miss = (Vec3::new(-5.0, 0.0, 0.6), Vec3::X);
+    check(cast!(ellipsoid), &hits, miss, Some(Vec3::ZERO));
+}
+
+#[test]
+fn height_field() {
+    // A flat 4×4 field, with its last column of cells sloping up to 2.0.
+    let mut heights = vec![vec![0.0; 5]; 5];
+    heights[4] = vec![2.0; 5];
+    let field = HeightField { heights, size: Vec2::splat(4.0) };
+    let slope = Vec3::new(-2.0, 1.0, 0.0);
+    let diagonal = Vec3::new(1.0, 0.0, 0.2);
+    let hits = [
+        hit(Vec3::new(0.5, 5.0, 0.5), -Vec3::Y, 5.0, Vec3::Y),
+        // Both sides of the surface can be hit.
+        hit(Vec3::new(0.5, -5.0, 0.5), Vec3::Y, 5.0, -Vec3::Y),
+        // Crosses the flat cells before hitting the slope at x = 1.25.
+        hit(Vec3::new(-5.0, 0.5, 0.3), Vec3::X, 6.25, slope),
+        hit(
+            Vec3::new(-5.0, 0.5, -1.9),
+            diagonal.normalize(),
+            6.25 * diagonal.length(),
+            slope,
+        ),
+    ];
+    // Crosses all cells above the surface.
+    let miss = (Vec3::new(-5.0, 2.5, -1.9), diagonal);
+    check(cast!(field), &hits, miss, None);
+    // Outside of the grid.
+    let miss = (Vec3::new(3.0, 5.0, 0.0), -Vec3::Y);
+    check(cast!(field), &[], miss, None);
+}
+
+#[test]
+fn height_field_diagonals() {
+    // Cells are split along alternating diagonals, the raised corner at
+    // `heights[2][0]` is only on one triangle of the odd cell.
+    let mut heights = vec![vec![0.0; 2]; 3];
+    heights[2][0] = 1.0;
+    let field = HeightField { heights, size: Vec2::new(2.0, 1.0) };
+    let hits = [
+        hit(
+            Vec3::new(0.75, 5.0, -0.25),
+            -Vec3::Y,
+            4.5,
+            Vec3::new(-1.0, 1.0, 1.0),
+        ),
+        hit(Vec3::new(0.2, 5.0, 0.3), -Vec3::Y, 5.0, Vec3::Y),
+        hit(Vec3::new(-0.5, 5.0, 0.0), -Vec3::Y, 5.0, Vec3::Y),
+    ];
+    let miss = (Vec3::new(0.0, 5.0, 2.0), -Vec3::Y);
+    check(cast!(field), &hits, miss, None);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "reflect")]
+
+use bevy_cool_shapes::*;
+use bevy_math::Vec2;
+#[cfg(feature = "serde")]
+use bevy_math::Vec3;
+#[cfg(feature = "serde")]
+use bevy_reflect::serde::{ReflectDeserializer, ReflectSerializer};
+#[cfg(feature = "serde")]
+use bevy_reflect::TypeRegistry;
+use bevy_reflect::{FromReflect, GetPath, Reflect};
+#[cfg(feature = "serde")]
+use serde::de::DeserializeSeed;
+
+#[test]
+fn edit_fields() {
+    let mut capsule = Capsule { radius: 1.0, segment_height: 2.0 };
+    *capsule.get_path_mut::<f32>("radius").unwrap() = 3.0;
+    assert_eq!(capsule.radius, 3.0);
+
+    let mut pyramid = Pyramid {
+        base: Shape2d::Disc(Disc { radius: 1.0 }),
+        height: 2.0,
+    };
+    *pyramid.get_path_mut::<f32>("base.disc.radius").unwrap() = 0.5;
+    *pyramid.get_path_mut::<f32>("height").unwrap() = 4.0;
+    assert!(matches!(pyramid.base, Shape2d::Disc(Disc { radius }) if radius == 0.5));
+    assert_eq!(pyramid.height, 4.0);
+    assert!(pyramid.path("base.rectangle").is_err());
+}
+
+#[test]
+fn apply_other_variant() {
+    let mut shape = Shape2d::Disc(Disc { radius: 1.0 });
+    let rectangle = Shape2d::Rectangle(Rectangle { size: Vec2::new(1.0, 2.0) });
+    shape.apply(rectangle.clone_value().as_ref());
+    assert!(matches!(shape, Shape2d::Rectangle(Rectangle { size }) if size == Vec2::new(1.0, 2.0)));
+
+    // Values that are not structs are ignored.
+    shape.apply(&2.0_f32);
+    assert!(matches!(shape, Shape2d::Rectangle(_)));
+    let mut pyramid = Pyramid { base: shape, height: 1.0 };
+    pyramid.apply(&2.0_f32);
+    assert_eq!(pyramid.height, 1.0);
+}
+
+#[test]
+fn from_reflect() {
+    let extruded = Extruded {
+        base: Shape2d::Polygon(Polygon::new([Vec2::ZERO, Vec2::X, Vec2::Y])),
+        height: 2.0,
+    };
+    let copy = Extruded::<Shape2d>::from_reflect(extruded.clone_value().as_ref()).unwrap();
+    assert!(matches!(&copy.base, Shape2d::Polygon(polygon) if polygon.tris.len() == 1));
+    assert_eq!(copy.height, 2.0);
+
+    let field = HeightField { heights: vec![vec![0.0, 1.0]; 2], size: Vec2::ONE };
+    let copy = HeightField::from_reflect(field.clone_value().as_ref()).unwrap();
+    assert_eq!(copy.heights, field.heights);
+}
+
+/// Check that `shape` is the same after going through a [`ReflectSerializer`],
+/// the way shapes are saved in scenes.
+///
+/// `reflect_partial_eq` always fails on `f32` fields, so we compare the
+/// serde serialization instead.
+#[cfg(feature = "serde")]
+fn round_trip<T: FromReflect + serde::Serialize>(shape: T) {
+    let mut registry = TypeRegistry::default();
+    registry.register::<f32>();
+    registry.register::<Vec2>();
+    registry.register::<Vec3>();
+    register_types(&mut registry);
+
+    let serializer = ReflectSerializer::new(&shape, &registry);
+    let serialized = ron::to_string(&serializer).unwrap();
+    let mut deserializer = ron::Deserializer::from_str(&serialized).unwrap();
+    let value = ReflectDeserializer::new(&registry)
+        .deserialize(&mut deserializer)
+        .unwrap();
+    let deserialized = T::from_reflect(value.as_ref()).unwrap();
+    assert_eq!(
+        ron::to_string(&shape).unwrap(),
+        ron::to_string(&deserialized).unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_with_registry() {
+    round_trip(Pyramid {
+        base: Shape2d::RoundedRectangle(RoundedRectangle { size: Vec2::ONE, bevel: 0.2 }),
+        height: 1.0,
+    });
+    round_trip(Extruded {
+        base: Shape2d::Polygon(Polygon::new([Vec2::ZERO, Vec2::X, Vec2::Y])),
+        height: 1.0,
+    });
+    round_trip(Lines {
+        points: vec![Vec3::ZERO, Vec3::X],
+        lines: vec![[0, 1]],
+    });
+    round_trip(Cuboid { size: Vec3::new(1.0, 2.0, 3.0) });
+    round_trip(HeightField { heights: vec![vec![0.0, 1.0]; 2], size: Vec2::ONE });
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "rand")]
+
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+const SAMPLES: usize = 10_000;
+const EPSILON: f32 = 1e-4;
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0x5eed)
+}
+
+type Sampler<'a, P> = &'a dyn Fn(&mut dyn RngCore) -> P;
+
+/// Check that the points of `sample_interior` are inside the shape and the
+/// ones of `sample_boundary` are on its surface, according to `sdf`.
+fn check_samples<P: Copy + std::fmt::Debug>(
+    sdf: impl Fn(P) -> f32,
+    interior: Option<Sampler<P>>,
+    boundary: Sampler<P>,
+) {
+    let mut rng = rng();
+    for _ in 0..1000 {
+        if let Some(interior) = interior {
+            let point = interior(&mut rng);
+            assert!(sdf(point) <= EPSILON, "{point:?} is outside");
+        }
+        let point = boundary(&mut rng);
+        assert!(
+            sdf(point).abs() <= EPSILON,
+            "{point:?} is not on the boundary"
+        );
+    }
+}
+
+/// How many of `SAMPLES` points of `sample` match `predicate`.
+fn fraction(sample: impl Fn(&mut dyn RngCore) -> Vec3, predicate: impl Fn(Vec3) -> bool) -> f32 {
+    let mut rng = rng();
+    let matching = (0..SAMPLES).filter(|_| predicate(sample(&mut rng))).count();
+    matching as f32 / SAMPLES as f32
+}
+
+fn assert_near(fraction: f32, expected: f32) {
+    assert!(
+        (fraction - expected).abs() < 0.02,
+        "got {fraction}, expected {expected}"
+    );
+}
+
+fn shapes_2d() -> Vec<Shape2d> {
+    vec![
+        Shape2d::Disc(Disc { radius: 1.5 }),
+        Shape2d::HalfDisc(HalfDisc { radius: 2.0 }),
+        Shape2d::QuarterDisc(QuarterDisc { radius: 0.5 }),
+        Shape2d::Annulus(Annulus { inner_radius: 1.0, outer_radius: 1.5 }),
+        Shape2d::Ellipse(Ellipse { half_axes: Vec2::new(2.0, 0.5) }),
+        Shape2d::Rectangle(Rectangle { size: Vec2::new(1.0, 2.0) }),
+        Shape2d::RoundedRectangle(RoundedRectangle { size: Vec2::new(3.0, 2.0), bevel: 0.25 }),
+        Shape2d::RoundedRectangle(RoundedRectangle { size: Vec2::new(3.0, 2.0), bevel: 0.0 }),
+        Shape2d::Triangle(Triangle { a: Vec2::ZERO, b: Vec2::X, c: Vec2::new(0.3, 1.2) }),
+        Shape2d::Polygon(Polygon::with_holes(
+            [
+                Vec2::ZERO,
+                Vec2::new(4.0, 0.0),
+                Vec2::new(4.0, 4.0),
+                Vec2::new(0.0, 4.0),
+            ],
+            [[Vec2::ONE, Vec2::new(1.0, 2.0), Vec2::new(2.0, 2.0)]],
+        )),
+    ]
+}
+
+#[test]
+fn samples_2d_on_shape() {
+    for shape in shapes_2d() {
+        check_samples(
+            |p| shape.sdf(p),
+            Some(&|rng| shape.sample_interior(rng)),
+            &|rng| shape.sample_boundary(rng),
+        );
+    }
+}
+
+#[test]
+fn samples_3d_on_shape() {
+    macro_rules! check {
+        ($($shape:expr),* $(,)?) => {$({
+            let shape = $shape;
+            check_samples(
+                |p| shape.sdf(p),
+                Some(&|rng| shape.sample_interior(rng)),
+                &|rng| shape.sample_boundary(rng),
+            );
+        })*};
+    }
+    check!(
+        Pyramid {
+            base: Rectangle { size: Vec2::new(1.0, 2.0) },
+            height: 2.0
+        },
+        Pyramid {
+            base: Triangle { a: Vec2::ZERO, b: Vec2::X, c: Vec2::Y },
+            height: 1.0,
+        },
+        Extruded {
+            base: Shape2d::Disc(Disc { radius: 1.0 }),
+            height: 0.5
+        },
+        Extruded { base: HalfDisc { radius: 2.0 }, height: 3.0 },
+        Sphere { radius: 1.0 },
+        HalfSphere { radius: 2.0 },
+        Capsule { radius: 0.5, segment_height: 2.0 },
+        Cuboid { size: Vec3::new(1.0, 2.0, 3.0) },
+        RoundedCuboid { size: Vec3::new(1.0, 2.0, 3.0), bevel: 0.25 },
+        RoundedCuboid { size: Vec3::new(1.0, 2.0, 3.0), bevel: 0.0 },
+        Cone { height: 3.0, base_radius: 1.0 },
+        Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z },
+        Cylinder { height: 2.0, radius: 0.5 },
+        Torus { major_radius: 2.0, minor_radius: 0.5 },
+        Ellipsoid { half_axes: Vec3::new(1.0, 2.0, 0.5) },
+    );
+    let lines = Lines {
+        points: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+        lines: vec![[0, 1], [1, 2]],
+    };
+    check_samples(|p| lines.sdf(p), None, &|rng| lines.sample_boundary(rng));
+    let field = HeightField {
+        heights: vec![vec![0.0, 1.0], vec![0.5, 0.25]],
+        size: Vec2::new(10.0, 10.0),
+    };
+    check_samples(|p| field.sdf(p), None, &|rng| field.sample_boundary(rng));
+}
+
+#[test]
+fn sphere_is_not_biased_toward_poles() {
+    let sphere = Sphere { radius: 1.0 };
+    let polar = |p: Vec3| p.y.abs() > 0.5;
+    // Slices of a sphere of the same thickness have the same area.
+    assert_near(fraction(|rng| sphere.sample_boundary(rng), polar), 0.5);
+    // The volume of a ball above half its radius is 5/32 of the ball, on each side.
+    assert_near(
+        fraction(|rng| sphere.sample_interior(rng), polar),
+        5.0 / 16.0,
+    );
+}
+
+#[test]
+fn capsule_caps_and_body() {
+    let capsule = Capsule { radius: 1.0, segment_height: 2.0 };
+    let in_caps = |p: Vec3| p.y.abs() > 1.0;
+    // The caps have the same area as the body, and 2/5 of the volume.
+    assert_near(fraction(|rng| capsule.sample_boundary(rng), in_caps), 0.5);
+    assert_near(fraction(|rng| capsule.sample_interior(rng), in_caps), 0.4);
+}
+
+#[test]
+fn polygon_triangles_weighted_by_area() {
+    // A triangle of area 0.5 and one of area 1.5.
+    let polygon = Polygon {
+        points: vec![Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::new(-3.0, 0.0)],
+        lines: vec![[0, 1], [1, 2], [2, 3], [3, 0]],
+        tris: vec![[0, 1, 2], [0, 2, 3]],
+    };
+    let mut rng = rng();
+    let small = (0..SAMPLES)
+        .filter(|_| polygon.sample_interior(&mut rng).x > 0.0)
+        .count();
+    assert_near(small as f32 / SAMPLES as f32, 0.25);
+}
+
+#[test]
+fn height_field_cells_weighted_by_area() {
+    // A flat cell of area 1, and a slope of area √2.
+    let field = HeightField {
+        heights: vec![vec![0.0, 0.0, 1.0], vec![0.0, 0.0, 1.0]],
+        size: Vec2::new(1.0, 2.0),
+    };
+    let slope = std::f32::consts::SQRT_2;
+    let on_slope = |p: Vec3| p.z > 0.0;
+    let expected = slope / (1.0 + slope);
+    assert_near(
+        fraction(|rng| field.sample_boundary(rng), on_slope),
+        expected,
+    );
+}
@@ -0,0 +1,323 @@
+use std::f32::consts::TAU;
+
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+
+const EPSILON: f32 = 1e-4;
+
+/// Check that `sdf` is `distance` at each point, negative inside.
+fn check<T: Copy + std::fmt::Debug>(sdf: impl Fn(T) -> f32, distances: &[(T, f32)]) {
+    for (point, distance) in distances {
+        let found = sdf(*point);
+        assert!(
+            (found - distance).abs() < EPSILON,
+            "{point:?}: {found} != {distance}"
+        );
+    }
+}
+fn v2(x: f32, y: f32) -> Vec2 {
+    Vec2::new(x, y)
+}
+fn v3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+/// Points on the unit sphere, away from the axes.
+fn directions() -> impl Iterator<Item = Vec3> {
+    (0..16).flat_map(|i| {
+        let longitude = i as f32 * TAU / 16.0 + 0.1;
+        [-0.7, -0.2, 0.3, 0.8].map(|y: f32| {
+            let ring = (1.0 - y * y).sqrt();
+            v3(ring * longitude.cos(), y, ring * longitude.sin())
+        })
+    })
+}
+
+#[test]
+fn round_shapes() {
+    let sphere = Sphere { radius: 2.0 };
+    check(
+        |p| sphere.sdf(p),
+        &[
+            (Vec3::ZERO, -2.0),
+            (v3(0.0, 0.0, 2.0), 0.0),
+            (v3(3.0, 4.0, 0.0), 3.0),
+        ],
+    );
+    let disc = Disc { radius: 2.0 };
+    check(
+        |p| disc.sdf(p),
+        &[(Vec2::ZERO, -2.0), (v2(0.0, 2.0), 0.0), (v2(3.0, 4.0), 3.0)],
+    );
+    let torus = Torus { major_radius: 2.0, minor_radius: 0.5 };
+    check(
+        |p| torus.sdf(p),
+        &[
+            (v3(2.0, 0.0, 0.0), -0.5),
+            (v3(0.0, 0.0, -2.5), 0.0),
+            (Vec3::ZERO, 1.5),
+            (v3(0.0, 1.0, 2.0), 0.5),
+        ],
+    );
+}
+
+#[test]
+fn half_sphere() {
+    let half = HalfSphere { radius: 2.0 };
+    check(
+        |p| half.sdf(p),
+        &[
+            // Closer to the flat disc than to the dome.
+            (v3(0.0, 0.5, 0.0), -0.5),
+            (v3(1.0, 0.5, 0.0), -0.5),
+            (v3(0.0, 1.5, 0.0), -0.5),
+            (v3(0.0, -1.0, 0.0), 1.0),
+            (v3(0.0, 3.0, 0.0), 1.0),
+            // Below the rim, the closest point is on its edge.
+            (v3(0.0, -1.0, 3.0), 2f32.sqrt()),
+            (v3(1.0, 0.0, 0.0), 0.0),
+            (v3(0.0, 2.0, 0.0), 0.0),
+            (v3(1.2, 1.6, 0.0), 0.0),
+        ],
+    );
+}
+
+#[test]
+fn half_and_quarter_discs() {
+    let half = HalfDisc { radius: 2.0 };
+    check(
+        |p| half.sdf(p),
+        &[
+            (v2(0.0, 0.5), -0.5),
+            (v2(0.0, 1.5), -0.5),
+            (v2(0.0, -1.0), 1.0),
+            (v2(3.0, -1.0), 2f32.sqrt()),
+            (v2(-1.0, 0.0), 0.0),
+            (v2(-1.2, 1.6), 0.0),
+        ],
+    );
+    let quarter = QuarterDisc { radius: 2.0 };
+    check(
+        |p| quarter.sdf(p),
+        &[
+            (v2(0.5, 1.0), -0.5),
+            (v2(-1.0, 1.0), 1.0),
+            (v2(-1.0, -1.0), 2f32.sqrt()),
+            (v2(3.0, -1.0), 2f32.sqrt()),
+            (v2(0.0, 1.0), 0.0),
+            (v2(1.2, 1.6), 0.0),
+        ],
+    );
+}
+
+#[test]
+fn capsule_and_cylinder() {
+    let capsule = Capsule { radius: 1.0, segment_height: 2.0 };
+    let cap = v3(0.0, 1.5, 0.5);
+    check(
+        |p| capsule.sdf(p),
+        &[
+            (Vec3::ZERO, -1.0),
+            (cap, cap.distance(Vec3::Y) - 1.0),
+            (v3(2.0, 0.5, 0.0), 1.0),
+            (v3(0.0, -3.0, 0.0), 1.0),
+            (v3(0.0, 2.0, 0.0), 0.0),
+            (v3(0.0, 1.0, 1.0), 0.0),
+        ],
+    );
+    let cylinder = Cylinder { radius: 1.0, height: 2.0 };
+    check(
+        |p| cylinder.sdf(p),
+        &[
+            (Vec3::ZERO, -1.0),
+            (v3(0.0, 0.8, 0.5), -0.2),
+            (v3(0.0, 2.0, 0.0), 1.0),
+            (v3(2.0, 2.0, 0.0), 2f32.sqrt()),
+            (v3(0.6, 0.0, 0.8), 0.0),
+        ],
+    );
+}
+
+#[test]
+fn cone() {
+    // The apex is at the top, the base at the bottom.
+    let cone = Cone { height: 2.0, base_radius: 1.0 };
+    check(
+        |p| cone.sdf(p),
+        &[
+            (Vec3::ZERO, -1.0 / 5f32.sqrt()),
+            (v3(0.0, -0.9, 0.0), -0.1),
+            (v3(0.0, -2.0, 0.0), 1.0),
+            (v3(0.0, 2.0, 0.0), 1.0),
+            (v3(0.0, 0.0, 0.5), 0.0),
+            (v3(0.5, -1.0, 0.0), 0.0),
+        ],
+    );
+}
+
+#[test]
+fn flat_shapes() {
+    let cuboid = Cuboid { size: v3(2.0, 4.0, 6.0) };
+    check(
+        |p| cuboid.sdf(p),
+        &[
+            (Vec3::ZERO, -1.0),
+            (v3(2.0, 0.0, 0.0), 1.0),
+            (v3(2.0, 3.0, 0.0), 2f32.sqrt()),
+            (v3(1.0, 2.0, 3.0), 0.0),
+        ],
+    );
+    let rounded = RoundedCuboid { size: Vec3::splat(2.0), bevel: 0.5 };
+    check(
+        |p| rounded.sdf(p),
+        &[
+            (Vec3::ZERO, -1.0),
+            (v3(2.0, 0.0, 0.0), 1.0),
+            (v3(2.0, 2.0, 0.0), 4.5f32.sqrt() - 0.5),
+            (v3(1.0, 0.5, 0.0), 0.0),
+        ],
+    );
+    let tetrahedron = Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z };
+    check(
+        |p| tetrahedron.sdf(p),
+        &[
+            (Vec3::splat(0.1), -0.1),
+            (v3(-1.0, 0.2, 0.2), 1.0),
+            (Vec3::ONE, 2.0 / 3f32.sqrt()),
+            (v3(0.2, 0.3, 0.0), 0.0),
+        ],
+    );
+    let lines = Lines {
+        points: vec![Vec3::ZERO, Vec3::X * 2.0, v3(2.0, 2.0, 0.0)],
+        lines: vec![[0, 1], [1, 2]],
+    };
+    check(
+        |p| lines.sdf(p),
+        &[
+            (v3(1.0, 1.0, 0.0), 1.0),
+            (v3(-3.0, 0.0, 4.0), 5.0),
+            (v3(1.0, 0.0, 0.0), 0.0),
+        ],
+    );
+}
+
+#[test]
+fn pyramid_and_extruded() {
+    let base = Shape2d::Rectangle(Rectangle { size: Vec2::splat(2.0) });
+    let pyramid = Pyramid { base, height: 2.0 };
+    // The sides are on planes like `2x + y = 1`.
+    let side = v3(1.0, 1.0, 0.0);
+    check(
+        |p| pyramid.sdf(p),
+        &[
+            (v3(0.0, -0.5, 0.0), -0.5),
+            (v3(0.0, -2.0, 0.0), 1.0),
+            (v3(0.0, 2.0, 0.0), 1.0),
+            (side, 2.0 / 5f32.sqrt()),
+            (v3(0.5, 0.0, 0.0), 0.0),
+        ],
+    );
+    let base = Shape2d::Annulus(Annulus { inner_radius: 1.0, outer_radius: 2.0 });
+    let extruded = Extruded { base, height: 2.0 };
+    check(
+        |p| extruded.sdf(p),
+        &[
+            (v3(1.5, 0.0, 0.0), -0.5),
+            (v3(0.0, 0.8, 1.5), -0.2),
+            (Vec3::ZERO, 1.0),
+            (v3(1.5, 3.0, 0.0), 2.0),
+            (v3(0.0, 2.0, 3.0), 2f32.sqrt()),
+            (v3(0.0, 1.0, -1.5), 0.0),
+        ],
+    );
+}
+
+#[test]
+fn height_field() {
+    let flat = HeightField {
+        heights: vec![vec![0.0; 3]; 3],
+        size: Vec2::splat(2.0),
+    };
+    check(
+        |p| flat.sdf(p),
+        &[
+            // Both sides are outside.
+            (v3(0.3, 1.0, -0.2), 1.0),
+            (v3(0.5, -2.0, 0.5), 2.0),
+            (v3(3.0, 0.0, 0.0), 2.0),
+            (v3(0.5, 0.0, -1.0), 0.0),
+        ],
+    );
+    // The raised corner is only on one triangle of its cell, see
+    // `height_field_diagonals` in `tests/ray.rs`.
+    let mut heights = vec![vec![0.0; 2]; 3];
+    heights[2][0] = 1.0;
+    let field = HeightField { heights, size: Vec2::new(2.0, 1.0) };
+    check(
+        |p| field.sdf(p),
+        &[
+            (v3(0.75, 0.5, -0.25), 0.0),
+            (v3(0.2, 0.0, 0.3), 0.0),
+            (v3(0.75, 0.0, -0.25), 0.5 / 3f32.sqrt()),
+        ],
+    );
+}
+
+#[test]
+fn ellipsoid() {
+    let axes = v3(1.0, 2.0, 3.0);
+    let ellipsoid = Ellipsoid { half_axes: axes };
+    // Exact along the axes, and inside along the smallest one.
+    check(
+        |p| ellipsoid.sdf(p),
+        &[
+            (Vec3::ZERO, -1.0),
+            (v3(0.5, 0.0, 0.0), -0.5),
+            (v3(2.0, 0.0, 0.0), 1.0),
+            (v3(0.0, -3.0, 0.0), 1.0),
+            (v3(0.0, 0.0, 5.0), 2.0),
+        ],
+    );
+    for direction in directions() {
+        let surface = direction * axes;
+        assert!(ellipsoid.sdf(surface).abs() < EPSILON, "{surface}");
+        assert!(ellipsoid.sdf(surface * 0.5) < 0.0, "{surface}");
+        assert!(ellipsoid.sdf(surface * 1.5) > 0.0, "{surface}");
+        // Close to the surface, the distance is almost exact.
+        let normal = (surface / (axes * axes)).normalize();
+        let near = ellipsoid.sdf(surface + normal * 0.01);
+        assert!((near - 0.01).abs() < 1e-3, "{surface}: {near}");
+        let near = ellipsoid.sdf(surface - normal * 0.01);
+        assert!((near + 0.01).abs() < 1e-3, "{surface}: {near}");
+    }
+    // Equal axes make a sphere.
+    let round = Ellipsoid { half_axes: Vec3::splat(2.0) };
+    let sphere = Sphere { radius: 2.0 };
+    for point in directions().map(|d| d * 3.0) {
+        assert!((round.sdf(point) - sphere.sdf(point)).abs() < EPSILON);
+        assert!((round.sdf(point / 3.0) - sphere.sdf(point / 3.0)).abs() < EPSILON);
+    }
+}
+
+#[test]
+fn ellipse() {
+    let axes = v2(1.0, 2.0);
+    let ellipse = Ellipse { half_axes: axes };
+    check(
+        |p| ellipse.sdf(p),
+        &[
+            (Vec2::ZERO, -1.0),
+            (v2(-0.5, 0.0), -0.5),
+            (v2(3.0, 0.0), 2.0),
+            (v2(0.0, -3.0), 1.0),
+        ],
+    );
+    for direction in directions().map(|d| v2(d.x, d.z).normalize()) {
+        let surface = direction * axes;
+        assert!(ellipse.sdf(surface).abs() < EPSILON, "{surface}");
+        assert!(ellipse.sdf(surface * 0.5) < 0.0, "{surface}");
+        assert!(ellipse.sdf(surface * 1.5) > 0.0, "{surface}");
+        let normal = (surface / (axes * axes)).normalize();
+        let near = ellipse.sdf(surface + normal * 0.01);
+        assert!((near - 0.01).abs() < 1e-3, "{surface}: {near}");
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "serde")]
+
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Check that `shape` is the same after going through RON.
+///
+/// Shapes do not implement `PartialEq`, so we compare their serialized form.
+fn round_trip<T: Serialize + DeserializeOwned>(shape: T) {
+    let serialized = ron::to_string(&shape).unwrap();
+    let deserialized: T = ron::from_str(&serialized).unwrap();
+    assert_eq!(serialized, ron::to_string(&deserialized).unwrap());
+}
+
+fn shapes_2d() -> Vec<Shape2d> {
+    vec![
+        Shape2d::Disc(Disc { radius: 1.5 }),
+        Shape2d::HalfDisc(HalfDisc { radius: 2.0 }),
+        Shape2d::QuarterDisc(QuarterDisc { radius: 0.5 }),
+        Shape2d::Annulus(Annulus { inner_radius: 1.0, outer_radius: 1.5 }),
+        Shape2d::Ellipse(Ellipse { half_axes: Vec2::new(2.0, 0.5) }),
+        Shape2d::Rectangle(Rectangle { size: Vec2::new(1.0, 2.0) }),
+        Shape2d::RoundedRectangle(RoundedRectangle { size: Vec2::new(3.0, 2.0), bevel: 0.25 }),
+        Shape2d::Triangle(Triangle { a: Vec2::ZERO, b: Vec2::X, c: Vec2::new(0.3, 1.2) }),
+        Shape2d::Polygon(Polygon::with_holes(
+            [
+                Vec2::ZERO,
+                Vec2::new(4.0, 0.0),
+                Vec2::new(4.0, 4.0),
+                Vec2::new(0.0, 4.0),
+            ],
+            [[Vec2::ONE, Vec2::new(1.0, 2.0), Vec2::new(2.0, 2.0)]],
+        )),
+    ]
+}
+
+#[test]
+fn shapes_2d_round_trip() {
+    for shape in shapes_2d() {
+        round_trip(shape);
+    }
+    round_trip(Disc { radius: 1.5 });
+    round_trip(Polygon::new([Vec2::ZERO, Vec2::X, Vec2::Y]));
+}
+
+#[test]
+fn nested_bases_round_trip() {
+    for base in shapes_2d() {
+        round_trip(Pyramid { base: base.clone(), height: 2.0 });
+        round_trip(Extruded { base, height: 0.5 });
+    }
+    round_trip(Pyramid { base: Rectangle { size: Vec2::ONE }, height: 1.0 });
+    round_trip(Extruded {
+        base: Triangle { a: Vec2::ZERO, b: Vec2::X, c: Vec2::Y },
+        height: 1.0,
+    });
+}
+
+#[test]
+fn shapes_3d_round_trip() {
+    round_trip(Sphere { radius: 1.0 });
+    round_trip(HalfSphere { radius: 2.0 });
+    round_trip(Capsule { radius: 0.5, segment_height: 2.0 });
+    round_trip(Cuboid { size: Vec3::new(1.0, 2.0, 3.0) });
+    round_trip(RoundedCuboid { size: Vec3::new(1.0, 2.0, 3.0), bevel: 0.1 });
+    round_trip(Cone { height: 3.0, base_radius: 1.0 });
+    round_trip(Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z });
+    round_trip(Lines {
+        points: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+        lines: vec![[0, 1], [1, 2]],
+    });
+    round_trip(Cylinder { height: 2.0, radius: 0.5 });
+    round_trip(Torus { major_radius: 2.0, minor_radius: 0.5 });
+    round_trip(Ellipsoid { half_axes: Vec3::new(1.0, 2.0, 0.5) });
+    round_trip(HeightField {
+        heights: vec![vec![0.0, 1.0], vec![0.5, 0.25]],
+        size: Vec2::new(10.0, 10.0),
+    });
+}
+
+#[test]
+fn deserialize_from_ron() {
+    let pyramid: Pyramid = ron::from_str("(base: Disc((radius: 2.0)), height: 3.0)").unwrap();
+    assert!(matches!(pyramid.base, Shape2d::Disc(Disc { radius }) if radius == 2.0));
+    assert_eq!(pyramid.height, 3.0);
+
+    let cuboid: Cuboid = ron::from_str("(size: (1.0, 2.0, 3.0))").unwrap();
+    assert_eq!(cuboid.size, Vec3::new(1.0, 2.0, 3.0));
+}
@@ -0,0 +1,54 @@
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+
+/// A unit square only implementing the required methods of [`Shape2dBase`].
+struct Square;
+impl Shape2dBase for Square {
+    fn boundary(&self, _: usize) -> Vec<Vec<Vec2>> {
+        vec![vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO]]
+    }
+    fn triangulate(&self, _: usize) -> Vec<[Vec2; 3]> {
+        vec![
+            [Vec2::ZERO, Vec2::X, Vec2::ONE],
+            [Vec2::ZERO, Vec2::ONE, Vec2::Y],
+        ]
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        Vec2::new(
+            (direction.x > 0.0) as u8 as f32,
+            (direction.y > 0.0) as u8 as f32,
+        )
+    }
+}
+
+#[test]
+fn default_methods() {
+    assert_eq!(Square.area(), 1.0);
+    assert_eq!(Square.perimeter(), 4.0);
+    assert!(Square.contains(Vec2::splat(0.5)));
+    assert!(!Square.contains(Vec2::new(1.5, 0.5)));
+    assert_eq!(Square.sdf(Vec2::new(0.5, 0.25)), -0.25);
+    assert_eq!(Square.sdf(Vec2::new(3.0, 0.5)), 2.0);
+    assert_eq!(Square.validate(), Ok(()));
+
+    let hit = Square
+        .cast_ray(Vec2::new(-1.0, 0.5), Vec2::X, 10.0)
+        .unwrap();
+    assert_eq!((hit.toi, hit.normal), (1.0, -Vec2::X));
+    let inside = Square.cast_ray(Vec2::splat(0.5), Vec2::X, 10.0).unwrap();
+    assert_eq!((inside.toi, inside.normal), (0.0, Vec2::ZERO));
+    assert!(Square
+        .cast_ray(Vec2::new(-1.0, 2.0), Vec2::X, 10.0)
+        .is_none());
+}
+
+#[test]
+fn custom_base() {
+    let extruded = Extruded { base: Square, height: 2.0 };
+    assert_eq!(extruded.volume(), 2.0);
+    assert!(extruded.contains(Vec3::new(0.5, 0.9, 0.5)));
+    let hit = extruded
+        .cast_ray(Vec3::new(0.5, 5.0, 0.5), -Vec3::Y, 10.0)
+        .unwrap();
+    assert_eq!(hit.toi, 4.0);
+}
@@ -0,0 +1,98 @@
+use bevy_cool_shapes::*;
+use bevy_math::{Vec2, Vec3};
+
+#[test]
+fn dimensions() {
+    assert!(Sphere::try_new(1.0).is_ok());
+    let negative = Sphere::try_new(-1.0).unwrap_err();
+    assert_eq!(
+        negative,
+        ShapeError::InvalidDimension { name: "radius", value: -1.0 }
+    );
+    assert_eq!(
+        negative.to_string(),
+        "`radius` must be non-negative and finite, got -1"
+    );
+    let infinite = Cylinder::try_new(f32::INFINITY, 1.0).unwrap_err();
+    assert!(matches!(
+        infinite,
+        ShapeError::InvalidDimension { name: "height", .. }
+    ));
+    let nan = Cuboid::try_new(Vec3::new(1.0, f32::NAN, 1.0)).unwrap_err();
+    assert!(matches!(
+        nan,
+        ShapeError::InvalidDimension { name: "size", .. }
+    ));
+
+    // Zero is fine where the shape stays well defined.
+    assert!(Capsule::try_new(1.0, 0.0).is_ok());
+    assert!(Annulus::try_new(0.0, 1.0).is_ok());
+    assert!(RoundedCuboid::try_new(Vec3::ONE, 0.0).is_ok());
+    assert!(Cuboid::try_new(Vec3::new(1.0, 0.0, 1.0)).is_ok());
+}
+
+#[test]
+fn ellipses() {
+    assert!(Ellipsoid::try_new(Vec3::new(1.0, 2.0, 3.0)).is_ok());
+    let zero = ShapeError::ZeroDimension { name: "half_axes" };
+    let flat = Ellipsoid::try_new(Vec3::new(1.0, 0.0, 3.0));
+    assert_eq!(flat.unwrap_err(), zero);
+    assert_eq!(Ellipse::try_new(Vec2::new(0.0, 1.0)).unwrap_err(), zero);
+    let negative = Ellipse::try_new(Vec2::new(1.0, -1.0)).unwrap_err();
+    assert_eq!(
+        negative,
+        ShapeError::InvalidDimension { name: "half_axes", value: -1.0 }
+    );
+}
+
+#[test]
+fn shape_specific() {
+    let bevel = RoundedRectangle::try_new(Vec2::new(1.0, 4.0), 0.6).unwrap_err();
+    assert_eq!(bevel, ShapeError::BevelTooLarge { bevel: 0.6, max: 0.5 });
+    let inner = Annulus::try_new(2.0, 1.0).unwrap_err();
+    let expected = ShapeError::InnerRadiusTooLarge { inner_radius: 2.0, outer_radius: 1.0 };
+    assert_eq!(inner, expected);
+
+    let point = Tetrahedron::try_new(Vec3::ZERO, Vec3::X, Vec3::Y * f32::NAN, Vec3::Z);
+    assert_eq!(point.unwrap_err(), ShapeError::NonFinitePoint { index: 2 });
+    let lines = Lines::try_new(vec![Vec3::ZERO, Vec3::X], vec![[0, 1], [1, 2]]);
+    assert_eq!(
+        lines.unwrap_err(),
+        ShapeError::IndexOutOfRange { index: 2, len: 2 }
+    );
+    let points = vec![Vec2::ZERO, Vec2::X, Vec2::Y];
+    let polygon = Polygon::try_new(points, vec![[0, 1]], vec![[0, 1, 3]]);
+    assert_eq!(
+        polygon.unwrap_err(),
+        ShapeError::IndexOutOfRange { index: 3, len: 3 }
+    );
+
+    // 3d shapes check their base.
+    let base = Shape2d::Disc(Disc { radius: -1.0 });
+    let pyramid = Pyramid::try_new(base, 1.0).unwrap_err();
+    assert_eq!(
+        pyramid,
+        ShapeError::InvalidDimension { name: "radius", value: -1.0 }
+    );
+}
+
+#[test]
+fn height_field() {
+    let size = Vec2::ONE;
+    assert!(HeightField::try_new(vec![vec![0.0; 3]; 2], size).is_ok());
+    let small = HeightField::try_new(vec![vec![0.0; 3]], size).unwrap_err();
+    assert_eq!(
+        small,
+        ShapeError::HeightFieldTooSmall { rows: 1, columns: 3 }
+    );
+    let ragged = vec![vec![0.0; 3], vec![0.0; 3], vec![0.0; 2]];
+    let ragged = HeightField::try_new(ragged, size).unwrap_err();
+    let expected = ShapeError::RaggedHeightField { row: 2, len: 2, expected: 3 };
+    assert_eq!(ragged, expected);
+    let non_finite = vec![vec![0.0; 3], vec![0.0, 0.0, f32::INFINITY]];
+    let non_finite = HeightField::try_new(non_finite, size).unwrap_err();
+    assert_eq!(
+        non_finite,
+        ShapeError::NonFiniteHeight { row: 1, column: 2 }
+    );
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use bevy_cool_shapes_render::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(RenderableShapesPlugin)
+        .add_startup_system(setup)
+        .add_system(orbit)
+        .add_system(trail)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn_bundle(PerspectiveCameraBundle::new_3d())
+        .insert(Transform::from_xyz(25., 10., 25.).looking_at(Vec3::ZERO, Vec3::Y));
+}
+
+fn orbit_position(time: &Time) -> Vec3 {
+    let angle = time.seconds_since_startup() as f32;
+    Vec3::new(angle.cos(), 0.0, angle.sin()) * 8.0
+}
+
+// Drawn every frame, so it follows the orbit.
+fn orbit(time: Res<Time>, mut painter: ShapePainter) {
+    let transform = Transform::from_translation(orbit_position(&time));
+    painter.set_width(100.0);
+    painter.outline(Sphere { radius: 1.0 }, transform, Color::YELLOW);
+}
+
+// Each disc stays for two seconds, leaving a trail behind the sphere.
+fn trail(time: Res<Time>, mut painter: ShapePainter, mut timer: Local<f32>) {
+    *timer += time.delta_seconds();
+    if *timer < 0.2 {
+        return;
+    }
+    *timer = 0.0;
+    let transform = Transform::from_translation(orbit_position(&time))
+        .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2));
+    painter.set_width(30.0);
+    painter.outline_for(2.0, Disc { radius: 0.5 }, transform, Color::CYAN);
+}
@@ -0,0 +1,25 @@
+//! Load an outline from `assets/capsule.shape.ron`, edit the file while the
+//! example is running to see the changes.
+use bevy::asset::AssetServerSettings;
+use bevy::prelude::*;
+use bevy_cool_shapes_render::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .insert_resource(AssetServerSettings { watch_for_changes: true, ..default() })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(RenderableShapesPlugin)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+    commands
+        .spawn_bundle(PerspectiveCameraBundle::new_3d())
+        .insert(Transform::from_xyz(25., 10., 25.).looking_at(Vec3::ZERO, Vec3::Y));
+    commands.spawn_bundle(ShapeAssetBundle {
+        shape: assets.load("capsule.shape.ron"),
+        ..default()
+    });
+}
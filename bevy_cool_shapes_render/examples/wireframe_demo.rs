@@ -65,12 +65,8 @@ fn run_animation(
     }
 }
 
-fn star_lines() -> impl Iterator<Item = [usize; 2]> {
-    (0..11).map(|i| [i, (i + 1) % 10])
-}
-
 fn star_points() -> impl Iterator<Item = Vec2> {
-    (0..11).map(|i| {
+    (0..10).map(|i| {
         let even = i % 2 == 0;
         let angle = TAU32 / 10. * (i as f32);
         let radius = if even { 2.0 } else { 1.0 };
@@ -93,11 +89,7 @@ fn setup(
     let [x, y, z] = Vec3::AXES;
     let one = Vec3::ONE;
 
-    let star = Polygon {
-        points: star_points().collect(),
-        lines: star_lines().collect(),
-        tris: vec![],
-    };
+    let star = Polygon::new(star_points());
     #[rustfmt::skip]
     let shapes: [OutlineableShape; I] = [
         Disc { radius: 3. }.into(),
@@ -108,7 +100,7 @@ fn setup(
         star.clone().into(),
         Pyramid { base: Shape2d::Polygon(star.clone()), height: 3.0 }.into(),
         Extruded { base: Shape2d::Polygon(star), height: 3.0 }.into(),
-        Sphere { radius: 2.3 }.into(),
+        OutlineableShape::from(Sphere { radius: 2.3 }).with_density(8),
         HalfSphere { radius: 2.0 }.into(),
         Capsule { radius: 1.3, segment_height: 5.0 }.into(),
         Cuboid { size: one * 2.3 }.into(),
@@ -182,11 +174,18 @@ fn setup(
     let animations = animations.into_iter();
     let iterator = shapes.zip(colors).zip(animations).enumerate();
     for (i, ((shape, (color, width)), (anim, pos))) in iterator {
+        let fill_color = *color.clone().set_a(0.1);
+        // The sphere bobbing in and out of the cube stays visible through it.
+        let depth_check = i != 8;
+        let lines = shape
+            .clone()
+            .lines(color, width, if i == 0 { 0.4 } else { 0.0 });
         let mut entity = commands.spawn_bundle(ShapeOutlineBundle {
-            shape: shape.lines(color, width, if i == 0 { 0.4 } else { 0.0 }),
+            shape: lines.with_depth_check(depth_check),
             transform: Transform::from_translation(pos),
             ..default()
         });
+        entity.insert(shape.fill(fill_color).with_depth_check(depth_check));
         if let Some(animation) = anim {
             entity.insert(animation);
         }
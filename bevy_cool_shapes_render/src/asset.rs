@@ -0,0 +1,256 @@
+//! Loading shapes from `.shape.ron` files.
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::{BoxedFuture, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::outline::OutlineableShape;
+use crate::render::{IntoOutline, ShapeOutline};
+
+/// A shape and its outline style, loaded from a `.shape.ron` file.
+///
+/// Entities with a `Handle<ShapeAsset>` get a [`ShapeOutline`] once the asset
+/// is loaded, it is updated each time the file changes if the
+/// `AssetServerSettings::watch_for_changes` is set. See
+/// [`ShapeAssetBundle`](crate::bundles::ShapeAssetBundle). The
+/// [`ShapeOutline`] is removed when the asset is removed.
+///
+/// All fields but `shape` are optional, they default to the values of
+/// [`ShapeOutline::default`]:
+///
+/// ```ron
+/// (
+///     shape: (density: 8, shape: Capsule((radius: 1.0, segment_height: 2.0))),
+///     color: Rgba(red: 0.0, green: 1.0, blue: 1.0, alpha: 1.0),
+///     width: 2.0,
+/// )
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, TypeUuid)]
+#[uuid = "7f1d2c4e-3b8a-4e6f-9d05-a2c6e81b4f37"]
+pub struct ShapeAsset {
+    pub shape: OutlineableShape,
+    #[serde(default = "default_color")]
+    pub color: Color,
+    #[serde(default = "default_width")]
+    pub width: f32,
+    #[serde(default)]
+    pub depth_bias: f32,
+    #[serde(default = "default_depth_check")]
+    pub depth_check: bool,
+}
+fn default_color() -> Color {
+    ShapeOutline::default().color()
+}
+fn default_width() -> f32 {
+    ShapeOutline::default().width()
+}
+fn default_depth_check() -> bool {
+    ShapeOutline::default().depth_check()
+}
+impl ShapeAsset {
+    /// A [`ShapeOutline`] with this shape and style.
+    pub fn outline(&self) -> ShapeOutline {
+        self.shape
+            .clone()
+            .lines(self.color, self.width, self.depth_bias)
+            .with_depth_check(self.depth_check)
+    }
+    /// Set the shape and style of `outline` to this one.
+    ///
+    /// The shape is only replaced if it differs, so that the lines are not
+    /// rebuilt when only the style changed.
+    fn update(&self, outline: &mut ShapeOutline) {
+        if !same_shape(outline.shape(), &self.shape) {
+            outline.set_shape(self.shape.clone());
+        }
+        outline.set_color(self.color);
+        outline.set_width(self.width);
+        outline.set_depth_bias(self.depth_bias);
+        outline.set_depth_check(self.depth_check);
+    }
+}
+
+/// Whether `a` and `b` serialize the same, custom shapes can't be compared
+/// so they are never the same.
+fn same_shape(a: &OutlineableShape, b: &OutlineableShape) -> bool {
+    match (ron::to_string(a), ron::to_string(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ShapeAssetLoader;
+impl AssetLoader for ShapeAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let asset: ShapeAsset = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(asset));
+            Ok(())
+        })
+    }
+    fn extensions(&self) -> &[&str] {
+        &["shape.ron"]
+    }
+}
+
+type ShapeAssetOutline<'a> = (
+    Entity,
+    ChangeTrackers<Handle<ShapeAsset>>,
+    &'a Handle<ShapeAsset>,
+    Option<&'a mut ShapeOutline>,
+);
+/// Insert or update the [`ShapeOutline`] of entities which
+/// `Handle<ShapeAsset>` was just added or loaded, and remove it when the
+/// asset is removed.
+///
+/// The outline is updated through its setters, so that `update_outline`
+/// rebuilds the lines.
+pub(crate) fn update_shape_assets(
+    mut cmds: Commands,
+    mut events: EventReader<AssetEvent<ShapeAsset>>,
+    assets: Res<Assets<ShapeAsset>>,
+    mut entities: Query<ShapeAssetOutline>,
+) {
+    let mut loaded = HashSet::default();
+    let mut removed = HashSet::default();
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                loaded.insert(handle.id);
+            }
+            AssetEvent::Removed { handle } => {
+                removed.insert(handle.id);
+            }
+        }
+    }
+    for (entity, tracker, handle, outline) in entities.iter_mut() {
+        if removed.contains(&handle.id) && outline.is_some() {
+            cmds.entity(entity).remove::<ShapeOutline>();
+            continue;
+        }
+        if !tracker.is_changed() && !loaded.contains(&handle.id) {
+            continue;
+        }
+        let asset = match assets.get(handle) {
+            Some(asset) => asset,
+            None => continue,
+        };
+        match outline {
+            Some(mut outline) => asset.update(&mut outline),
+            None => {
+                cmds.entity(entity).insert(asset.outline());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+    use bevy::core::CorePlugin;
+    use bevy_cool_shapes::{Cuboid, Sphere};
+
+    use super::*;
+    use crate::outline::Outlineable;
+    use crate::strips::LineStrips;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<ShapeAsset>()
+            .add_system(update_shape_assets);
+        app
+    }
+    fn asset(shape: impl Into<OutlineableShape>, color: Color) -> ShapeAsset {
+        ShapeAsset {
+            shape: shape.into(),
+            color,
+            width: 1.0,
+            depth_bias: 0.0,
+            depth_check: true,
+        }
+    }
+    /// Asset events are sent at the end of the frame, so they are handled on
+    /// the next one.
+    fn update_twice(app: &mut App) {
+        app.update();
+        app.update();
+    }
+
+    #[test]
+    fn outline_follows_asset() {
+        let mut app = app();
+        let mut assets = app.world.resource_mut::<Assets<ShapeAsset>>();
+        let handle = assets.add(asset(Sphere { radius: 1.0 }, Color::RED));
+        let entity = app.world.spawn().insert(handle.clone()).id();
+        update_twice(&mut app);
+        let outline = app.world.get::<ShapeOutline>(entity).unwrap();
+        let version = outline.shape_version();
+        assert_eq!(outline.color(), Color::RED);
+
+        // Changing only the style keeps the shape.
+        let mut assets = app.world.resource_mut::<Assets<ShapeAsset>>();
+        assets.get_mut(&handle).unwrap().color = Color::BLUE;
+        update_twice(&mut app);
+        let outline = app.world.get::<ShapeOutline>(entity).unwrap();
+        assert_eq!(outline.color(), Color::BLUE);
+        assert_eq!(outline.shape_version(), version);
+
+        let mut assets = app.world.resource_mut::<Assets<ShapeAsset>>();
+        let cuboid = Cuboid { size: Vec3::ONE };
+        assets.get_mut(&handle).unwrap().shape = cuboid.into();
+        update_twice(&mut app);
+        let outline = app.world.get::<ShapeOutline>(entity).unwrap();
+        assert_ne!(outline.shape_version(), version);
+        assert_eq!(outline.shape().aabb().max, Vec3::splat(0.5));
+
+        let mut assets = app.world.resource_mut::<Assets<ShapeAsset>>();
+        assets.remove(&handle);
+        update_twice(&mut app);
+        assert!(app.world.get::<ShapeOutline>(entity).is_none());
+    }
+
+    struct Point;
+    impl Outlineable for Point {
+        fn outline(&self, _: usize) -> LineStrips {
+            let mut strips = LineStrips::new();
+            strips.push_segment(Vec3::ZERO, Vec3::X);
+            strips
+        }
+    }
+
+    #[test]
+    fn custom_shapes_are_always_replaced() {
+        let sphere = OutlineableShape::from(Sphere { radius: 1.0 });
+        assert!(same_shape(&sphere, &sphere.clone()));
+        let custom = OutlineableShape::custom(Point);
+        assert!(!same_shape(&custom, &custom.clone()));
+
+        let mut app = app();
+        let mut assets = app.world.resource_mut::<Assets<ShapeAsset>>();
+        let handle = assets.add(asset(custom, Color::RED));
+        let entity = app.world.spawn().insert(handle.clone()).id();
+        update_twice(&mut app);
+        let version = app
+            .world
+            .get::<ShapeOutline>(entity)
+            .unwrap()
+            .shape_version();
+
+        // Custom shapes can't be compared, so they are replaced even when
+        // only the style changed.
+        let mut assets = app.world.resource_mut::<Assets<ShapeAsset>>();
+        assets.get_mut(&handle).unwrap().color = Color::BLUE;
+        update_twice(&mut app);
+        let outline = app.world.get::<ShapeOutline>(entity).unwrap();
+        assert_ne!(outline.shape_version(), version);
+    }
+}
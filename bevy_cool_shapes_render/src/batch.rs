@@ -0,0 +1,419 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_polyline::prelude::*;
+
+use crate::material::{OutlineStyle, PolylineAssets};
+use crate::render::ShapeOutline;
+use crate::strips::LineStrips;
+
+/// The local space lines of an outline rendered in a batch.
+///
+/// In batched mode, this replaces the [`LineMesh`](crate::render::LineMesh)
+/// child of outlines.
+#[derive(Component)]
+pub(crate) struct BatchedOutline {
+    pub(crate) strips: LineStrips,
+    /// The [`ShapeOutline::shape_version()`] the strips were built from.
+    pub(crate) shape_version: u32,
+}
+
+/// Marks entities spawned by [`batch_outlines`].
+#[derive(Component)]
+pub(crate) struct OutlineBatch;
+
+/// One polyline entity per outline style, see [`batch_outlines`].
+#[derive(Default)]
+pub(crate) struct OutlineBatches {
+    batches: HashMap<OutlineStyle, Entity>,
+    /// The outlines in the batches, to rebuild them when one is hidden.
+    drawn: Vec<Entity>,
+}
+
+type BatchedOutlines<'a> = (
+    Entity,
+    &'a ShapeOutline,
+    &'a BatchedOutline,
+    &'a GlobalTransform,
+    &'a ComputedVisibility,
+);
+type ChangedBatchedOutlines = (
+    With<BatchedOutline>,
+    Or<(
+        Changed<GlobalTransform>,
+        Changed<BatchedOutline>,
+        Changed<ShapeOutline>,
+    )>,
+);
+
+/// Merge all visible batched outlines with the same style into a single
+/// polyline, in world space.
+///
+/// The batches are only rebuilt when a batched outline changed, moved, or
+/// became visible or hidden.
+pub(crate) fn batch_outlines(
+    mut cmds: Commands,
+    mut batches: ResMut<OutlineBatches>,
+    mut assets: PolylineAssets,
+    batch_polylines: Query<&Handle<Polyline>, With<OutlineBatch>>,
+    outlines: Query<BatchedOutlines>,
+    changed: Query<(), ChangedBatchedOutlines>,
+    removed: RemovedComponents<BatchedOutline>,
+) {
+    let is_drawn = |(_, outline, _, _, visibility): &BatchedOutlines| {
+        visibility.is_visible && outline.depth_check()
+    };
+    let drawn: Vec<_> = outlines.iter().filter(is_drawn).map(|(e, ..)| e).collect();
+    let unchanged = changed.is_empty() && removed.iter().next().is_none();
+    if unchanged && drawn == batches.drawn {
+        return;
+    }
+    batches.drawn = drawn;
+
+    let mut merged: HashMap<OutlineStyle, (PolylineMaterial, LineStrips)> = HashMap::default();
+    for (_, outline, batched, transform, _) in outlines.iter().filter(is_drawn) {
+        let (_, strips) = merged
+            .entry(outline.style())
+            .or_insert_with(|| (outline.polyline_material(), LineStrips::new()));
+        let mut world_strips = batched.strips.clone();
+        let affine = transform.compute_affine();
+        world_strips.map_vertices(|v| affine.transform_point3(v));
+        strips.append(&world_strips);
+    }
+    batches.batches.retain(|style, entity| {
+        let keep = merged.contains_key(style);
+        if !keep {
+            cmds.entity(*entity).despawn();
+        }
+        keep
+    });
+    for (style, (material, strips)) in merged {
+        let vertices = strips.polyline_vertices();
+        let batch = batches.batches.get(&style);
+        match batch.and_then(|entity| batch_polylines.get(*entity).ok()) {
+            Some(polyline) => {
+                if let Some(polyline) = assets.polylines.get_mut(polyline) {
+                    polyline.vertices = vertices;
+                }
+            }
+            None => {
+                let bundle = PolylineBundle {
+                    polyline: assets.polylines.add(Polyline { vertices }),
+                    material: assets.material(style, material),
+                    ..default()
+                };
+                let entity = cmds.spawn_bundle(bundle).insert(OutlineBatch).id();
+                batches.batches.insert(style, entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+    use bevy::asset::{Asset, HandleId};
+    use bevy::core::CorePlugin;
+    use bevy::ecs::event::{Events, ManualEventReader};
+    use bevy::render::primitives::Aabb;
+
+    use super::*;
+    use crate::material::{self, OutlineMaterials, ShapeMaterial, ShapeMaterials};
+    use crate::outline::{Outlineable, OutlineableShape};
+    use crate::render::{self, FillMesh, IntoOutline, LineMesh, ShapeFill};
+    use crate::RenderableShapesSettings;
+    use bevy_cool_shapes::Sphere;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Polyline>()
+            .add_asset::<PolylineMaterial>()
+            .add_asset::<Mesh>()
+            .add_asset::<ShapeMaterial>()
+            .insert_resource(RenderableShapesSettings { batch_outlines: true })
+            .init_resource::<OutlineMaterials>()
+            .init_resource::<ShapeMaterials>()
+            .init_resource::<OutlineBatches>()
+            .add_system(render::insert_outline)
+            .add_system(render::update_outline)
+            .add_system(render::remove_outline)
+            .add_system(render::insert_fill)
+            .add_system(render::update_fill)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                material::clear_unused_materials::<OutlineStyle, PolylineMaterial>
+                    .before(batch_outlines),
+            )
+            .add_system_to_stage(CoreStage::PostUpdate, batch_outlines);
+        app
+    }
+    fn sphere() -> OutlineableShape {
+        Sphere { radius: 1.0 }.into()
+    }
+    fn spawn(app: &mut App, color: Color, x: f32) -> Entity {
+        let outline = sphere().lines(color, 1.0, 0.0);
+        let transform = GlobalTransform::from_xyz(x, 0.0, 0.0);
+        let bundle = (outline, transform, ComputedVisibility::default());
+        app.world.spawn().insert_bundle(bundle).id()
+    }
+    /// The vertices of each batch, the largest first.
+    fn batches(app: &mut App) -> Vec<Vec<Vec3>> {
+        let mut query = app
+            .world
+            .query_filtered::<&Handle<Polyline>, With<OutlineBatch>>();
+        let polylines = app.world.resource::<Assets<Polyline>>();
+        let vertices = |handle| polylines.get(handle).unwrap().vertices.clone();
+        let mut batches: Vec<_> = query.iter(&app.world).map(vertices).collect();
+        batches.sort_by_key(|vertices| std::cmp::Reverse(vertices.len()));
+        batches
+    }
+    fn single_outline_len() -> usize {
+        sphere().outline().polyline_vertices().len()
+    }
+    /// Outlines are separated by a `NAN` vertex in a batch.
+    fn two_outlines_len() -> usize {
+        2 * single_outline_len() + 1
+    }
+    fn line_meshes(app: &mut App) -> usize {
+        app.world.query::<&LineMesh>().iter(&app.world).count()
+    }
+    fn hidden_line_meshes(app: &mut App) -> usize {
+        let mut query = app.world.query_filtered::<&Visibility, With<LineMesh>>();
+        let is_hidden = |visibility: &&Visibility| !visibility.is_visible;
+        query.iter(&app.world).filter(is_hidden).count()
+    }
+
+    #[test]
+    fn batches_outlines_by_style() {
+        let mut app = app();
+        spawn(&mut app, Color::RED, 0.0);
+        spawn(&mut app, Color::RED, 5.0);
+        spawn(&mut app, Color::BLUE, 0.0);
+        app.update();
+        let batches = batches(&mut app);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), two_outlines_len());
+        assert_eq!(batches[1].len(), single_outline_len());
+        assert!(batches[0].iter().any(|v| v.x > 4.0));
+        assert_eq!(line_meshes(&mut app), 0);
+    }
+
+    #[test]
+    fn depth_bias_is_part_of_the_style() {
+        let mut app = app();
+        let biased = spawn(&mut app, Color::RED, 0.0);
+        spawn(&mut app, Color::RED, 5.0);
+        let mut outline = app.world.get_mut::<ShapeOutline>(biased).unwrap();
+        outline.set_depth_bias(0.4);
+        app.update();
+        assert_eq!(batches(&mut app).len(), 2);
+    }
+
+    #[test]
+    fn hidden_outlines_are_not_batched() {
+        let mut app = app();
+        let hidden = spawn(&mut app, Color::RED, 0.0);
+        let shown = spawn(&mut app, Color::RED, 5.0);
+        app.update();
+        app.world
+            .get_mut::<ComputedVisibility>(hidden)
+            .unwrap()
+            .is_visible = false;
+        app.update();
+        assert_eq!(batches(&mut app)[0].len(), single_outline_len());
+
+        app.world
+            .get_mut::<ComputedVisibility>(shown)
+            .unwrap()
+            .is_visible = false;
+        app.update();
+        assert!(batches(&mut app).is_empty());
+    }
+
+    #[test]
+    fn depth_check_toggles_batching() {
+        let mut app = app();
+        let toggled = spawn(&mut app, Color::RED, 0.0);
+        spawn(&mut app, Color::RED, 5.0);
+        app.update();
+
+        let mut outline = app.world.get_mut::<ShapeOutline>(toggled).unwrap();
+        outline.set_depth_check(false);
+        app.update();
+        assert!(app.world.get::<BatchedOutline>(toggled).is_none());
+        assert_eq!(line_meshes(&mut app), 1);
+        assert_eq!(batches(&mut app)[0].len(), single_outline_len());
+
+        let mut outline = app.world.get_mut::<ShapeOutline>(toggled).unwrap();
+        outline.set_depth_check(true);
+        app.update();
+        assert!(app.world.get::<BatchedOutline>(toggled).is_some());
+        assert_eq!(line_meshes(&mut app), 0);
+        assert_eq!(batches(&mut app)[0].len(), two_outlines_len());
+    }
+
+    #[test]
+    fn respawned_lines_stay_hidden() {
+        let mut app = app();
+        app.insert_resource(RenderableShapesSettings { batch_outlines: false });
+        let entity = spawn(&mut app, Color::RED, 0.0);
+        app.world
+            .entity_mut(entity)
+            .insert(Visibility { is_visible: false });
+        app.update();
+        assert_eq!(hidden_line_meshes(&mut app), 1);
+
+        // The lines are respawned as a mesh drawn on top.
+        let mut outline = app.world.get_mut::<ShapeOutline>(entity).unwrap();
+        outline.set_depth_check(false);
+        app.update();
+        assert_eq!(line_meshes(&mut app), 1);
+        assert_eq!(hidden_line_meshes(&mut app), 1);
+    }
+
+    #[test]
+    fn hidden_shapes_stay_hidden_once_valid() {
+        let mut app = app();
+        app.insert_resource(RenderableShapesSettings { batch_outlines: false });
+        let invalid = OutlineableShape::from(Sphere { radius: -1.0 });
+        let bundle = (
+            invalid.clone().lines(Color::RED, 1.0, 0.0),
+            invalid.fill(Color::RED),
+            Visibility { is_visible: false },
+        );
+        let entity = app.world.spawn().insert_bundle(bundle).id();
+        app.update();
+        assert_eq!(line_meshes(&mut app), 0);
+
+        let mut outline = app.world.get_mut::<ShapeOutline>(entity).unwrap();
+        outline.set_shape(sphere());
+        let mut fill = app.world.get_mut::<ShapeFill>(entity).unwrap();
+        fill.set_shape(sphere());
+        app.update();
+        assert_eq!(hidden_line_meshes(&mut app), 1);
+        let mut fills = app.world.query_filtered::<&Visibility, With<FillMesh>>();
+        let fills: Vec<_> = fills.iter(&app.world).map(|v| v.is_visible).collect();
+        assert_eq!(fills, [false]);
+    }
+
+    /// The line and fill children of the shapes, with their asset handles.
+    fn children(app: &mut App) -> Vec<(Entity, HandleId)> {
+        let mut lines = app
+            .world
+            .query_filtered::<(Entity, &Handle<Polyline>), With<LineMesh>>();
+        let mut meshes = app
+            .world
+            .query_filtered::<(Entity, &Handle<Mesh>), Or<(With<LineMesh>, With<FillMesh>)>>();
+        let lines = lines.iter(&app.world).map(|(e, h)| (e, h.id));
+        let mut children: Vec<_> = lines.collect();
+        children.extend(meshes.iter(&app.world).map(|(e, h)| (e, h.id)));
+        children.sort();
+        children
+    }
+    fn modified<T: Asset>(app: &App, reader: &mut ManualEventReader<AssetEvent<T>>) -> usize {
+        let events = app.world.resource::<Events<AssetEvent<T>>>();
+        let is_modified = |event: &&_| matches!(event, AssetEvent::Modified { .. });
+        reader.iter(events).filter(is_modified).count()
+    }
+
+    #[test]
+    fn changes_update_children_in_place() {
+        let mut app = app();
+        app.insert_resource(RenderableShapesSettings { batch_outlines: false });
+        let with_depth = spawn(&mut app, Color::RED, 0.0);
+        let on_top = sphere().lines(Color::RED, 1.0, 0.0).with_depth_check(false);
+        let bundle = (on_top, sphere().fill(Color::RED));
+        let on_top = app.world.spawn().insert_bundle(bundle).id();
+        app.update();
+        let spawned = children(&mut app);
+        assert_eq!(spawned.len(), 3);
+        let mut polylines = ManualEventReader::<AssetEvent<Polyline>>::default();
+        let mut meshes = ManualEventReader::<AssetEvent<Mesh>>::default();
+        modified(&app, &mut polylines);
+        modified(&app, &mut meshes);
+
+        // Only the materials change with the style.
+        for entity in [with_depth, on_top] {
+            let mut outline = app.world.get_mut::<ShapeOutline>(entity).unwrap();
+            outline.set_color(Color::BLUE);
+        }
+        let mut fill = app.world.get_mut::<ShapeFill>(on_top).unwrap();
+        fill.set_color(Color::BLUE);
+        app.update();
+        assert_eq!(children(&mut app), spawned);
+        assert_eq!(modified(&app, &mut polylines), 0);
+        assert_eq!(modified(&app, &mut meshes), 0);
+
+        let radius_2 = || OutlineableShape::from(Sphere { radius: 2.0 });
+        for entity in [with_depth, on_top] {
+            let mut outline = app.world.get_mut::<ShapeOutline>(entity).unwrap();
+            outline.set_shape(radius_2());
+        }
+        let mut fill = app.world.get_mut::<ShapeFill>(on_top).unwrap();
+        fill.set_shape(radius_2());
+        app.update();
+        assert_eq!(children(&mut app), spawned);
+        assert_eq!(modified(&app, &mut polylines), 1);
+        assert_eq!(modified(&app, &mut meshes), 2);
+    }
+
+    struct Triangle;
+    impl Outlineable for Triangle {
+        fn outline(&self, _: usize) -> LineStrips {
+            let mut strips = LineStrips::new();
+            strips.push_strip([Vec3::ZERO, Vec3::X, Vec3::Y * 2.0, Vec3::ZERO]);
+            strips
+        }
+    }
+
+    #[test]
+    fn custom_shapes_are_drawn() {
+        let mut app = app();
+        app.insert_resource(RenderableShapesSettings { batch_outlines: false });
+        let outline = OutlineableShape::custom(Triangle).lines(Color::RED, 1.0, 0.0);
+        app.world.spawn().insert(outline);
+        app.update();
+        let mut lines = app
+            .world
+            .query_filtered::<(&Handle<Polyline>, &Aabb), With<LineMesh>>();
+        let (polyline, aabb) = lines.iter(&app.world).next().unwrap();
+        let polylines = app.world.resource::<Assets<Polyline>>();
+        let vertices = &polylines.get(polyline).unwrap().vertices;
+        assert_eq!(vertices, &Triangle.outline(1).polyline_vertices());
+        // The bounding box of custom shapes is the one of their outline.
+        assert_eq!(Vec3::from(aabb.center), Vec3::new(0.5, 1.0, 0.0));
+        assert_eq!(Vec3::from(aabb.half_extents), Vec3::new(0.5, 1.0, 0.0));
+    }
+
+    #[test]
+    fn removed_outlines_despawn_batches() {
+        let mut app = app();
+        let entity = spawn(&mut app, Color::RED, 0.0);
+        app.update();
+        assert_eq!(batches(&mut app).len(), 1);
+
+        app.world.entity_mut(entity).remove::<ShapeOutline>();
+        app.update();
+        assert!(app.world.get::<BatchedOutline>(entity).is_none());
+        assert!(batches(&mut app).is_empty());
+    }
+
+    #[test]
+    fn unchanged_batches_are_not_rebuilt() {
+        let mut app = app();
+        let entity = spawn(&mut app, Color::RED, 0.0);
+        app.update();
+        app.update();
+        let mut reader = ManualEventReader::<AssetEvent<Polyline>>::default();
+        modified(&app, &mut reader);
+        app.update();
+        assert_eq!(modified(&app, &mut reader), 0);
+
+        let mut transform = app.world.get_mut::<GlobalTransform>(entity).unwrap();
+        transform.translation.x = 3.0;
+        app.update();
+        assert_eq!(modified(&app, &mut reader), 1);
+        assert!(batches(&mut app)[0].iter().any(|v| v.x > 2.0));
+    }
+}
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 
-use crate::render::ShapeOutline;
+#[cfg(feature = "asset")]
+use crate::asset::ShapeAsset;
+use crate::render::{ShapeFill, ShapeOutline};
 
 #[derive(Bundle, Default)]
 pub struct ShapeOutlineBundle {
@@ -10,3 +12,24 @@ pub struct ShapeOutlineBundle {
     pub visibility: Visibility,
     pub computed_visibility: ComputedVisibility,
 }
+
+#[derive(Bundle, Default)]
+pub struct ShapeFillBundle {
+    pub shape: ShapeFill,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// An entity with the outline described by a [`ShapeAsset`], the
+/// [`ShapeOutline`] is added once the asset is loaded.
+#[cfg(feature = "asset")]
+#[derive(Bundle, Default)]
+pub struct ShapeAssetBundle {
+    pub shape: Handle<ShapeAsset>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
@@ -0,0 +1,333 @@
+use std::f32::consts::{FRAC_PI_2 as QUARTER, TAU};
+use std::iter;
+
+use bevy::math::{Mat2, Vec2, Vec3};
+use bevy::render::mesh::{Mesh, PrimitiveTopology};
+use bevy_cool_shapes::*;
+
+use crate::outline::{y3d, OutlineableShape};
+
+type Tri = [Vec3; 3];
+
+/// Revolve a `(radius, height)` profile around the Y axis.
+fn lathe(profile: &[Vec2], density: usize) -> Vec<Tri> {
+    let segments = density * 4;
+    let rotation = |i: usize| Mat2::from_angle(TAU * i as f32 / segments as f32);
+    let at = |p: Vec2, i| {
+        let v = rotation(i) * Vec2::X * p.x;
+        Vec3::new(v.x, p.y, v.y)
+    };
+    let mut tris = Vec::with_capacity(profile.len() * segments * 2);
+    for w in profile.windows(2) {
+        let (low, high) = (w[0], w[1]);
+        for i in 0..segments {
+            let (a, b) = (at(low, i), at(low, i + 1));
+            let (c, d) = (at(high, i), at(high, i + 1));
+            // Avoid degenerate triangles where the profile touches the axis.
+            if low.x != 0.0 {
+                tris.push([a, b, c]);
+            }
+            if high.x != 0.0 {
+                tris.push([c, b, d]);
+            }
+        }
+    }
+    tris
+}
+
+/// `(radius, height)` profile of a circle arc, from `start` to `end` angle.
+fn arc_profile(radius: f32, start: f32, end: f32, steps: usize) -> impl Iterator<Item = Vec2> {
+    (0..=steps).map(move |i| {
+        let angle = start + (end - start) * i as f32 / steps as f32;
+        Vec2::new(angle.cos(), angle.sin()) * radius
+    })
+}
+
+/// The surfaces of a 3d shape.
+pub(crate) trait Fill {
+    fn fill(&self, density: usize) -> Vec<Tri>;
+}
+impl<B: Shape2dBase> Fill for Pyramid<B> {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let half_height = Vec3::Y * self.height / 2.0;
+        let bottom = |v: Vec2| y3d(v) - half_height;
+        let walls = self.base.boundary(density).into_iter().flat_map(|loop_| {
+            let walls = loop_
+                .windows(2)
+                .map(|w| [bottom(w[0]), bottom(w[1]), half_height]);
+            walls.collect::<Vec<_>>()
+        });
+        let floor = self.base.triangulate(density).into_iter();
+        floor.map(|tri| tri.map(bottom)).chain(walls).collect()
+    }
+}
+impl<B: Shape2dBase> Fill for Extruded<B> {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let half_height = Vec3::Y * self.height / 2.0;
+        let bottom = |v: Vec2| y3d(v) - half_height;
+        let top = |v: Vec2| y3d(v) + half_height;
+        let walls = self.base.boundary(density).into_iter().flat_map(|loop_| {
+            let quad = |w: &[Vec2]| {
+                let (a, b) = (w[0], w[1]);
+                [[bottom(a), bottom(b), top(a)], [top(a), bottom(b), top(b)]]
+            };
+            loop_.windows(2).flat_map(quad).collect::<Vec<_>>()
+        });
+        let base = self.base.triangulate(density);
+        let floor = base.iter().map(|tri| tri.map(bottom));
+        let ceiling = base.iter().map(|tri| tri.map(top));
+        floor.chain(ceiling).chain(walls).collect()
+    }
+}
+impl Fill for Sphere {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let arc = arc_profile(self.radius, -QUARTER, QUARTER, density * 2);
+        lathe(&arc.collect::<Vec<_>>(), density)
+    }
+}
+impl Fill for HalfSphere {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let arc = arc_profile(self.radius, 0.0, QUARTER, density);
+        let profile: Vec<_> = iter::once(Vec2::ZERO).chain(arc).collect();
+        lathe(&profile, density)
+    }
+}
+impl Fill for Capsule {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let Capsule { radius, segment_height } = *self;
+        let offset = Vec2::Y * segment_height / 2.0;
+        let bottom = arc_profile(radius, -QUARTER, 0.0, density).map(|v| v - offset);
+        let top = arc_profile(radius, 0.0, QUARTER, density).map(|v| v + offset);
+        let profile: Vec<_> = bottom.chain(top).collect();
+        lathe(&profile, density)
+    }
+}
+impl Fill for Cuboid {
+    fn fill(&self, _: usize) -> Vec<Tri> {
+        let h = self.size / 2.0;
+        let corner = |i: usize| {
+            let sign = |bit| if i & bit == 0 { -1.0 } else { 1.0 };
+            Vec3::new(sign(1), sign(2), sign(4)) * h
+        };
+        // Each face as 4 corner indices, going around the face.
+        let faces = [
+            [0, 1, 3, 2],
+            [4, 6, 7, 5],
+            [0, 4, 5, 1],
+            [2, 3, 7, 6],
+            [0, 2, 6, 4],
+            [1, 5, 7, 3],
+        ];
+        faces
+            .iter()
+            .flat_map(|&[a, b, c, d]| {
+                let [a, b, c, d] = [a, b, c, d].map(corner);
+                [[a, b, c], [a, c, d]]
+            })
+            .collect()
+    }
+}
+/// A sphere split in octants, each octant moved to a corner of the cuboid.
+impl Fill for RoundedCuboid {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let RoundedCuboid { size, bevel } = *self;
+        let inner = size / 2.0 - bevel;
+        let quarter =
+            |start: f32| (0..=density).map(move |i| start + QUARTER * i as f32 / density as f32);
+        // The elevation of each ring, with the side of its corners on Y.
+        let bottom = quarter(-QUARTER).map(|elevation| (elevation, -1.0));
+        let top = quarter(0.0).map(|elevation| (elevation, 1.0));
+        let rings: Vec<_> = bottom.chain(top).collect();
+        // The azimuth of each column, with the side of its corners on X and Z.
+        let sides = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)];
+        let columns: Vec<_> = sides
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, side)| quarter(QUARTER * i as f32).map(move |a| (a, side)))
+            .collect();
+        let at = |(elevation, y): (f32, f32), (azimuth, (x, z)): (f32, (f32, f32))| {
+            let (sin, cos) = elevation.sin_cos();
+            let normal = Vec3::new(cos * azimuth.cos(), sin, cos * azimuth.sin());
+            inner * Vec3::new(x, y, z) + normal * bevel
+        };
+        let mut tris = Vec::with_capacity(rings.len() * columns.len() * 2 + 4);
+        for ring in rings.windows(2) {
+            let (low, high) = (ring[0], ring[1]);
+            for (i, column) in columns.iter().enumerate() {
+                let next = columns[(i + 1) % columns.len()];
+                let (a, b) = (at(low, *column), at(low, next));
+                let (c, d) = (at(high, *column), at(high, next));
+                tris.push([a, b, c]);
+                tris.push([c, b, d]);
+            }
+        }
+        // The octants only meet on the sides, close the top and bottom faces.
+        for y in [-1.0, 1.0] {
+            let corner = |(x, z)| Vec3::new(x * inner.x, y * size.y / 2.0, z * inner.z);
+            let [a, b, c, d] = sides.map(corner);
+            tris.push([a, b, c]);
+            tris.push([a, c, d]);
+        }
+        tris
+    }
+}
+impl Fill for Cone {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let half_height = self.height / 2.0;
+        let profile = [
+            Vec2::new(0.0, -half_height),
+            Vec2::new(self.base_radius, -half_height),
+            Vec2::new(0.0, half_height),
+        ];
+        lathe(&profile, density)
+    }
+}
+impl Fill for Tetrahedron {
+    fn fill(&self, _: usize) -> Vec<Tri> {
+        let Tetrahedron { a, b, c, d } = *self;
+        vec![[a, b, c], [a, b, d], [a, c, d], [b, c, d]]
+    }
+}
+impl Fill for Cylinder {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let Cylinder { height, radius } = *self;
+        let half_height = height / 2.0;
+        let profile = [
+            Vec2::new(0.0, -half_height),
+            Vec2::new(radius, -half_height),
+            Vec2::new(radius, half_height),
+            Vec2::new(0.0, half_height),
+        ];
+        lathe(&profile, density)
+    }
+}
+impl Fill for Torus {
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let center = Vec2::X * self.major_radius;
+        let tube = arc_profile(self.minor_radius, 0.0, TAU, density * 4);
+        lathe(&tube.map(|v| v + center).collect::<Vec<_>>(), density)
+    }
+}
+impl Fill for Ellipsoid {
+    /// A unit sphere, stretched by `half_axes`.
+    fn fill(&self, density: usize) -> Vec<Tri> {
+        let sphere = Sphere { radius: 1.0 }.fill(density);
+        let stretch = |tri: Tri| tri.map(|v| v * self.half_axes);
+        sphere.into_iter().map(stretch).collect()
+    }
+}
+impl Fill for HeightField {
+    fn fill(&self, _: usize) -> Vec<Tri> {
+        let HeightField { ref heights, size } = *self;
+        let x_len = heights.len();
+        let y_len = heights[0].len();
+        let x_coord = |x| size.x / ((x_len - 1) as f32) * (x as f32) - size.x / 2.0;
+        let y_coord = |y| size.y / ((y_len - 1) as f32) * (y as f32) - size.y / 2.0;
+        let point_at = |x: usize, y: usize| Vec3::new(x_coord(x), heights[x][y], y_coord(y));
+        let mut tris = Vec::with_capacity((x_len - 1) * (y_len - 1) * 2);
+        for x in 0..x_len - 1 {
+            for y in 0..y_len - 1 {
+                let (a, b) = (point_at(x, y), point_at(x + 1, y));
+                let (c, d) = (point_at(x, y + 1), point_at(x + 1, y + 1));
+                // Same diagonal as the outline's zig-zag pattern, which
+                // goes up even columns and down odd ones.
+                if x % 2 == 0 {
+                    tris.push([a, b, c]);
+                    tris.push([c, b, d]);
+                } else {
+                    tris.push([a, b, d]);
+                    tris.push([a, d, c]);
+                }
+            }
+        }
+        tris
+    }
+}
+
+impl OutlineableShape {
+    pub(crate) fn fill(&self) -> Vec<Tri> {
+        // See `OutlineableShape::outline`.
+        self.as_outlineable().fill(self.density.max(1))
+    }
+
+    /// A flat-shaded [`Mesh`] of this shape's surfaces.
+    pub(crate) fn fill_mesh(&self) -> Mesh {
+        let tris = self.fill();
+        let normal = |[a, b, c]: &Tri| (*b - *a).cross(*c - *a).normalize_or_zero().to_array();
+        let positions: Vec<[f32; 3]> = tris.iter().flatten().map(|v| v.to_array()).collect();
+        let normals: Vec<[f32; 3]> = tris.iter().flat_map(|t| [normal(t); 3]).collect();
+        let uvs = vec![[0.0_f32; 2]; positions.len()];
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashSet;
+
+    use super::*;
+
+    /// The ends of `segment`, as bits so that they can be hashed, in a
+    /// stable order.
+    fn edge_key([a, b]: [Vec3; 2]) -> [[u32; 3]; 2] {
+        let key = |v: Vec3| v.to_array().map(f32::to_bits);
+        let (a, b) = (key(a), key(b));
+        if a <= b {
+            [a, b]
+        } else {
+            [b, a]
+        }
+    }
+
+    /// Check that the fill of `shape` has no flat triangles and that their
+    /// total area is within `tolerance` of `area`.
+    fn check_area(shape: impl Into<OutlineableShape>, area: f32, tolerance: f32) {
+        let mut total = 0.0;
+        for [a, b, c] in shape.into().fill() {
+            let tri_area = (b - a).cross(c - a).length() / 2.0;
+            assert!(tri_area > 1e-6, "{:?} is flat", [a, b, c]);
+            total += tri_area;
+        }
+        assert!((total - area).abs() <= tolerance, "{total} != {area}");
+    }
+
+    #[test]
+    fn fill_covers_surface() {
+        check_area(Cuboid { size: Vec3::new(1.0, 2.0, 3.0) }, 22.0, 1e-5);
+        // At the default density, the circle is drawn as a 16-gon.
+        check_area(Disc { radius: 1.0 }, 8.0 * (TAU / 16.0).sin(), 1e-5);
+        let flat = HeightField {
+            heights: vec![vec![0.0; 4]; 3],
+            size: Vec2::new(2.0, 3.0),
+        };
+        check_area(flat, 6.0, 1e-5);
+        // A single slope, rising by 1.0 over 1.0.
+        let slope = HeightField {
+            heights: vec![vec![0.0; 2], vec![1.0; 2]],
+            size: Vec2::ONE,
+        };
+        check_area(slope, 2f32.sqrt(), 1e-5);
+    }
+
+    #[test]
+    fn height_field_fill_matches_outline() {
+        let heights = vec![
+            vec![0.0, 0.5, 0.2],
+            vec![1.0, 0.1, 0.3],
+            vec![0.4, 0.8, 0.0],
+            vec![0.2, 0.6, 0.9],
+        ];
+        let field = HeightField { heights, size: Vec2::new(3.0, 2.0) };
+        let shape = OutlineableShape::from(field);
+        let segments: HashSet<_> = shape.outline().segments().map(edge_key).collect();
+        for [a, b, c] in shape.fill() {
+            for edge in [[a, b], [b, c], [c, a]] {
+                assert!(segments.contains(&edge_key(edge)), "{edge:?} is not drawn");
+            }
+        }
+    }
+}
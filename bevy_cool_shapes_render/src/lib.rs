@@ -1,25 +1,120 @@
+#[cfg(feature = "asset")]
+mod asset;
+mod batch;
 mod bundles;
+mod fill;
+mod material;
 mod outline;
+mod painter;
+#[cfg(feature = "reflect")]
+mod reflect;
 mod render;
+mod strips;
 
 pub mod prelude {
-    pub use crate::bundles::ShapeOutlineBundle;
-    pub use crate::outline::OutlineableShape;
-    pub use crate::render::{IntoOutline, ShapeOutline};
-    pub use crate::RenderableShapesPlugin;
+    #[cfg(feature = "asset")]
+    pub use crate::asset::ShapeAsset;
+    #[cfg(feature = "asset")]
+    pub use crate::bundles::ShapeAssetBundle;
+    pub use crate::bundles::{ShapeFillBundle, ShapeOutlineBundle};
+    pub use crate::outline::{Outlineable, OutlineableShape};
+    pub use crate::painter::ShapePainter;
+    pub use crate::render::{IntoOutline, ShapeFill, ShapeOutline};
+    pub use crate::strips::LineStrips;
+    pub use crate::{RenderableShapesPlugin, RenderableShapesSettings};
     pub use bevy_cool_shapes::*;
 }
 
+use bevy::pbr::MaterialPlugin;
 use bevy::prelude::*;
-use bevy_polyline::PolylinePlugin;
+use bevy::render::view::VisibilitySystems;
+use bevy::transform::TransformSystem;
+use bevy_polyline::{prelude::PolylineMaterial, PolylinePlugin};
 
+use material::SHAPE_SHADER_HANDLE;
+use material::{OutlineMaterials, OutlineStyle, ShapeMaterial, ShapeMaterials, ShapeStyle};
+
+/// Configuration of [`RenderableShapesPlugin`].
+///
+/// Insert it before adding the plugin, changing it afterward has no effect.
+#[derive(Clone, Debug, Default)]
+pub struct RenderableShapesSettings {
+    /// Merge the outlines sharing the same style into a single polyline, in
+    /// world space.
+    ///
+    /// This reduces the number of draw calls to one per outline style, at the
+    /// cost of transforming every visible outline on the CPU on frames where
+    /// one of them moves, changes or is hidden. Use this when rendering
+    /// thousands of outlines. Outlines without depth checking are never batched.
+    ///
+    /// Batched outlines are not frustum culled: an outline is drawn whenever
+    /// its entity's `ComputedVisibility` is visible, which does not account
+    /// for the bounds of the outline.
+    pub batch_outlines: bool,
+}
+
+/// Render [`ShapeOutline`](prelude::ShapeOutline) and
+/// [`ShapeFill`](prelude::ShapeFill) components.
+///
+/// See [`RenderableShapesSettings`] for configuration.
 pub struct RenderableShapesPlugin;
 impl Plugin for RenderableShapesPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<RenderableShapesSettings>();
+        let settings = app.world.resource::<RenderableShapesSettings>().clone();
+        // The painter and the batches spawn entities with cached materials
+        // through commands, they are not visible to the cleanup until the end
+        // of the stage.
+        let mut clear_outline_materials =
+            material::clear_unused_materials::<OutlineStyle, PolylineMaterial>
+                .before(painter::paint_shapes);
+        if settings.batch_outlines {
+            clear_outline_materials = clear_outline_materials.before(batch::batch_outlines);
+            app.init_resource::<batch::OutlineBatches>()
+                .add_system_to_stage(
+                    CoreStage::PostUpdate,
+                    batch::batch_outlines.after(VisibilitySystems::CheckVisibility),
+                );
+        }
+
+        let mut shaders = app.world.resource_mut::<Assets<Shader>>();
+        let shader = Shader::from_wgsl(include_str!("shape.wgsl"));
+        shaders.set_untracked(SHAPE_SHADER_HANDLE, shader);
+
+        #[cfg(feature = "reflect")]
+        {
+            let registry = app.world.resource::<bevy::reflect::TypeRegistryArc>();
+            bevy_cool_shapes::register_types(&mut registry.write());
+            app.register_type::<prelude::OutlineableShape>()
+                .register_type::<prelude::ShapeOutline>()
+                .register_type::<prelude::ShapeFill>();
+        }
+
+        #[cfg(feature = "asset")]
+        app.add_asset::<asset::ShapeAsset>()
+            .init_asset_loader::<asset::ShapeAssetLoader>()
+            .add_system(asset::update_shape_assets.before(render::update_outline));
+
         app.add_plugin(PolylinePlugin)
+            .add_plugin(MaterialPlugin::<ShapeMaterial>::default())
             .add_system(render::insert_outline)
             .add_system(render::update_outlines_visibility)
             .add_system(render::remove_outline)
-            .add_system(render::update_outline);
+            .add_system(render::update_outline)
+            .add_system(render::insert_fill)
+            .add_system(render::remove_fill)
+            .add_system(render::update_fill)
+            .init_resource::<OutlineMaterials>()
+            .init_resource::<ShapeMaterials>()
+            .add_system_to_stage(CoreStage::PostUpdate, clear_outline_materials)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                material::clear_unused_materials::<ShapeStyle, ShapeMaterial>,
+            )
+            .init_resource::<painter::PaintQueue>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                painter::paint_shapes.before(TransformSystem::TransformPropagate),
+            );
     }
 }
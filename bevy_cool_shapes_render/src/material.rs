@@ -0,0 +1,259 @@
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use bevy::asset::Asset;
+use bevy::ecs::system::{lifetimeless::SRes, SystemParam, SystemParamItem};
+use bevy::pbr::{AlphaMode, MaterialPipeline, SpecializedMaterial};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::{
+    mesh::MeshVertexBufferLayout,
+    render_asset::{PrepareAssetError, RenderAsset},
+    render_resource::{
+        std140::{AsStd140, Std140},
+        *,
+    },
+    renderer::RenderDevice,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_polyline::prelude::{Polyline, PolylineMaterial};
+
+pub(crate) const SHAPE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 0x5a3e_96c1_d1f4_0b27);
+
+/// Unlit flat color material used for filled shapes and on-top lines.
+///
+/// Unlike [`StandardMaterial`], this lets us disable depth checking, so that
+/// the mesh is visible through other geometry.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "0c3ac9a6-52a5-4bd9-8bd8-d7cb3a5d0d1e"]
+pub(crate) struct ShapeMaterial {
+    pub(crate) color: Color,
+    pub(crate) depth_check: bool,
+}
+impl ShapeMaterial {
+    fn alpha_mode(&self) -> AlphaMode {
+        // Shapes drawn on top must be in the transparent pass, which is
+        // rendered after all the opaque geometry.
+        if self.color.a() < 1.0 || !self.depth_check {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        }
+    }
+}
+
+pub(crate) struct GpuShapeMaterial {
+    _buffer: Buffer,
+    bind_group: BindGroup,
+    alpha_mode: AlphaMode,
+    depth_check: bool,
+}
+
+impl RenderAsset for ShapeMaterial {
+    type ExtractedAsset = ShapeMaterial;
+    type PreparedAsset = GpuShapeMaterial;
+    type Param = (SRes<RenderDevice>, SRes<MaterialPipeline<Self>>);
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let color = Vec4::from(material.color.as_linear_rgba_f32());
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("shape_material_uniform_buffer"),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            contents: color.as_std140().as_bytes(),
+        });
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }],
+            label: Some("shape_material_bind_group"),
+            layout: &pipeline.material_layout,
+        });
+        Ok(GpuShapeMaterial {
+            _buffer: buffer,
+            bind_group,
+            alpha_mode: material.alpha_mode(),
+            depth_check: material.depth_check,
+        })
+    }
+}
+
+impl SpecializedMaterial for ShapeMaterial {
+    /// Whether to check depth.
+    type Key = bool;
+
+    fn key(material: &GpuShapeMaterial) -> bool {
+        material.depth_check
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        depth_check: bool,
+        _layout: &MeshVertexBufferLayout,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        if let (false, Some(depth)) = (depth_check, &mut descriptor.depth_stencil) {
+            depth.depth_compare = CompareFunction::Always;
+            depth.depth_write_enabled = false;
+        }
+        Ok(())
+    }
+
+    fn fragment_shader(_asset_server: &AssetServer) -> Option<Handle<Shader>> {
+        Some(SHAPE_SHADER_HANDLE.typed())
+    }
+
+    fn bind_group(material: &GpuShapeMaterial) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(Vec4::std140_size_static() as u64),
+                },
+                count: None,
+            }],
+            label: Some("shape_material_layout"),
+        })
+    }
+
+    fn alpha_mode(material: &GpuShapeMaterial) -> AlphaMode {
+        material.alpha_mode
+    }
+}
+
+fn color_key(color: Color) -> [u32; 4] {
+    color.as_rgba_f32().map(f32::to_bits)
+}
+
+/// The parameters of an outline [`PolylineMaterial`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct OutlineStyle {
+    color: [u32; 4],
+    width: u32,
+    perspective: bool,
+    depth_bias: u32,
+}
+impl OutlineStyle {
+    pub(crate) fn new(color: Color, width: f32, perspective: bool, depth_bias: f32) -> Self {
+        OutlineStyle {
+            color: color_key(color),
+            width: width.to_bits(),
+            perspective,
+            depth_bias: depth_bias.to_bits(),
+        }
+    }
+}
+impl From<&'_ PolylineMaterial> for OutlineStyle {
+    /// The style of lines without depth bias.
+    fn from(material: &PolylineMaterial) -> Self {
+        let PolylineMaterial { color, width, perspective } = *material;
+        OutlineStyle::new(color, width, perspective, 0.0)
+    }
+}
+
+/// The parameters of a [`ShapeMaterial`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ShapeStyle {
+    color: [u32; 4],
+    depth_check: bool,
+}
+impl From<&'_ ShapeMaterial> for ShapeStyle {
+    fn from(material: &ShapeMaterial) -> Self {
+        ShapeStyle {
+            color: color_key(material.color),
+            depth_check: material.depth_check,
+        }
+    }
+}
+
+/// Materials shared between all shapes with the same style.
+///
+/// This avoids creating one material per shape, when there is thousands of
+/// shapes of the same color.
+pub struct MaterialCache<K, M: Asset> {
+    handles: HashMap<K, Handle<M>>,
+    /// Whether materials were added since the last cleanup.
+    ///
+    /// Materials that stop being used are otherwise noticed through
+    /// `RemovedComponents`, so an entity switching to another cached material
+    /// keeps the old one alive until the next cleanup.
+    dirty: bool,
+}
+impl<K, M: Asset> Default for MaterialCache<K, M> {
+    fn default() -> Self {
+        MaterialCache { handles: HashMap::default(), dirty: false }
+    }
+}
+impl<K: Hash + Eq, M: Asset> MaterialCache<K, M> {
+    /// The material for `key`, adding it with `material` if not yet cached.
+    pub(crate) fn get(
+        &mut self,
+        key: K,
+        assets: &mut Assets<M>,
+        material: impl FnOnce() -> M,
+    ) -> Handle<M> {
+        let dirty = &mut self.dirty;
+        let handle = self.handles.entry(key).or_insert_with(|| {
+            *dirty = true;
+            assets.add(material())
+        });
+        handle.clone()
+    }
+}
+pub(crate) type OutlineMaterials = MaterialCache<OutlineStyle, PolylineMaterial>;
+pub(crate) type ShapeMaterials = MaterialCache<ShapeStyle, ShapeMaterial>;
+
+/// The assets of polylines drawn outside of [`ShapeOutline`] children, by the
+/// painter and the batches.
+///
+/// [`ShapeOutline`]: crate::render::ShapeOutline
+#[derive(SystemParam)]
+pub(crate) struct PolylineAssets<'w, 's> {
+    pub(crate) polylines: ResMut<'w, Assets<Polyline>>,
+    poly_mats: ResMut<'w, Assets<PolylineMaterial>>,
+    materials: ResMut<'w, OutlineMaterials>,
+    #[system_param(ignore)]
+    _lifetime: PhantomData<&'s ()>,
+}
+impl<'w, 's> PolylineAssets<'w, 's> {
+    /// The cached material for `style`, `material` is added if there is
+    /// none yet.
+    pub(crate) fn material(
+        &mut self,
+        style: OutlineStyle,
+        material: PolylineMaterial,
+    ) -> Handle<PolylineMaterial> {
+        self.materials.get(style, &mut self.poly_mats, || material)
+    }
+}
+
+/// Drop the cached materials not used by any entity, so that the assets get
+/// freed.
+pub(crate) fn clear_unused_materials<K, M>(
+    mut cache: ResMut<MaterialCache<K, M>>,
+    used: Query<&Handle<M>>,
+    removed: RemovedComponents<Handle<M>>,
+) where
+    K: Hash + Eq + Send + Sync + 'static,
+    M: Asset,
+{
+    if !cache.dirty && removed.iter().next().is_none() {
+        return;
+    }
+    cache.dirty = false;
+    let used: HashSet<_> = used.iter().map(|handle| handle.id).collect();
+    cache.handles.retain(|_, handle| used.contains(&handle.id));
+}
@@ -1,25 +1,180 @@
 use std::f32::consts::TAU;
+use std::fmt;
 use std::iter;
+use std::sync::Arc;
 
 use bevy::math::{Mat2, Vec2, Vec3, Vec3Swizzles};
+use bevy::render::mesh::{Mesh, PrimitiveTopology};
+use bevy::render::primitives;
 use bevy_cool_shapes::*;
 
+use crate::fill::Fill;
+use crate::strips::LineStrips;
+
+/// A shape that can be drawn by [`ShapeOutline`] and [`ShapeFill`].
+///
+/// This is implemented for all shapes of `bevy_cool_shapes`. Implement it
+/// for your own types and use [`OutlineableShape::custom`] to render them.
+///
+/// [`ShapeOutline`]: crate::render::ShapeOutline
+/// [`ShapeFill`]: crate::render::ShapeFill
+pub trait Outlineable: Send + Sync + 'static {
+    /// The line strips of this shape's outline.
+    ///
+    /// `density` is how many line segments are used to draw a quarter of a
+    /// circle, it is always at least 1.
+    fn outline(&self, density: usize) -> LineStrips;
+
+    /// The triangles of this shape's surfaces, used by [`ShapeFill`].
+    ///
+    /// By default, the shape has no surface.
+    ///
+    /// [`ShapeFill`]: crate::render::ShapeFill
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        let _ = density;
+        Vec::new()
+    }
+}
+
+/// A user-defined [`Outlineable`], see [`OutlineableShape::custom`].
+#[derive(Clone)]
+pub(crate) struct CustomShape(Arc<dyn Outlineable>);
+impl fmt::Debug for CustomShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CustomShape(..)")
+    }
+}
+
+/// Any [`Outlineable`] shape, with the `density` to draw it with.
+///
+/// With the `serde` feature, it can be serialized, unless it is a
+/// [custom shape](OutlineableShape::custom). With the `reflect` feature, it
+/// is reflected as a struct with a `density` field and a field named after
+/// the shape, for example `capsule`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutlineableShape {
-    density: usize,
-    shape: OutlineableShapeEnum,
+    pub(crate) density: usize,
+    pub(crate) shape: OutlineableShapeEnum,
+    /// Incremented each time the shape is modified through reflection, see
+    /// [`ShapeOutline::shape_version()`].
+    ///
+    /// [`ShapeOutline::shape_version()`]: crate::render::ShapeOutline::shape_version()
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) reflect_edits: u32,
 }
 impl Default for OutlineableShape {
     fn default() -> Self {
         OutlineableShape {
             density: 4,
             shape: OutlineableShapeEnum::Sphere(Sphere { radius: 1.0 }),
+            reflect_edits: 0,
         }
     }
 }
 
+impl OutlineableShape {
+    /// Render a shape not defined in `bevy_cool_shapes`.
+    pub fn custom(shape: impl Outlineable) -> Self {
+        Self::from(Arc::new(shape) as Arc<dyn Outlineable>)
+    }
+    /// How many line segments are used to draw a quarter of a circle.
+    pub fn density(&self) -> usize {
+        self.density
+    }
+    /// Set how many line segments are used to draw a quarter of a circle.
+    ///
+    /// Higher values result in smoother curves. Values lower than 1 are
+    /// treated as 1.
+    pub fn set_density(&mut self, density: usize) {
+        self.density = density.max(1);
+    }
+    /// See [`OutlineableShape::set_density`].
+    pub fn with_density(mut self, density: usize) -> Self {
+        self.set_density(density);
+        self
+    }
+    pub(crate) fn as_outlineable(&self) -> &dyn Outlineable {
+        use OutlineableShapeEnum::*;
+        match &self.shape {
+            Shape2d(shape) => shape,
+            Pyramid(shape) => shape,
+            Extruded(shape) => shape,
+            Sphere(shape) => shape,
+            HalfSphere(shape) => shape,
+            Capsule(shape) => shape,
+            Cuboid(shape) => shape,
+            RoundedCuboid(shape) => shape,
+            Cone(shape) => shape,
+            Tetrahedron(shape) => shape,
+            Lines(shape) => shape,
+            Cylinder(shape) => shape,
+            Torus(shape) => shape,
+            Ellipsoid(shape) => shape,
+            HeightField(shape) => shape,
+            Custom(CustomShape(shape)) => shape.as_ref(),
+        }
+    }
+    /// The bounding box of this shape, in local space.
+    ///
+    /// For custom shapes, this is the bounding box of their outline.
+    pub fn aabb(&self) -> Aabb {
+        use OutlineableShapeEnum::*;
+        match &self.shape {
+            Shape2d(shape) => shape.aabb(),
+            Pyramid(shape) => shape.aabb(),
+            Extruded(shape) => shape.aabb(),
+            Sphere(shape) => shape.aabb(),
+            HalfSphere(shape) => shape.aabb(),
+            Capsule(shape) => shape.aabb(),
+            Cuboid(shape) => shape.aabb(),
+            RoundedCuboid(shape) => shape.aabb(),
+            Cone(shape) => shape.aabb(),
+            Tetrahedron(shape) => shape.aabb(),
+            Lines(shape) => shape.aabb(),
+            Cylinder(shape) => shape.aabb(),
+            Torus(shape) => shape.aabb(),
+            Ellipsoid(shape) => shape.aabb(),
+            HeightField(shape) => shape.aabb(),
+            Custom(_) => Aabb::from_points(self.outline().vertices().iter().copied()),
+        }
+    }
+    /// Check that the shape is well-formed, custom shapes are always valid.
+    ///
+    /// Invalid shapes are not rendered, since computing their outline may
+    /// panic.
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        use OutlineableShapeEnum::*;
+        match &self.shape {
+            Shape2d(shape) => shape.validate(),
+            Pyramid(shape) => shape.validate(),
+            Extruded(shape) => shape.validate(),
+            Sphere(shape) => shape.validate(),
+            HalfSphere(shape) => shape.validate(),
+            Capsule(shape) => shape.validate(),
+            Cuboid(shape) => shape.validate(),
+            RoundedCuboid(shape) => shape.validate(),
+            Cone(shape) => shape.validate(),
+            Tetrahedron(shape) => shape.validate(),
+            Lines(shape) => shape.validate(),
+            Cylinder(shape) => shape.validate(),
+            Torus(shape) => shape.validate(),
+            Ellipsoid(shape) => shape.validate(),
+            HeightField(shape) => shape.validate(),
+            Custom(_) => Ok(()),
+        }
+    }
+    /// The bounding box of this shape as a bevy [`primitives::Aabb`], used
+    /// for frustum culling.
+    pub(crate) fn render_aabb(&self) -> primitives::Aabb {
+        let Aabb { min, max } = self.aabb();
+        primitives::Aabb::from_min_max(min, max)
+    }
+}
+
 #[derive(Debug, Clone)]
-enum OutlineableShapeEnum {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum OutlineableShapeEnum {
     Shape2d(Shape2d),
     Pyramid(Pyramid),
     Extruded(Extruded),
@@ -27,11 +182,16 @@ enum OutlineableShapeEnum {
     HalfSphere(HalfSphere),
     Capsule(Capsule),
     Cuboid(Cuboid),
+    RoundedCuboid(RoundedCuboid),
     Cone(Cone),
     Tetrahedron(Tetrahedron),
     Lines(Lines),
     Cylinder(Cylinder),
+    Torus(Torus),
+    Ellipsoid(Ellipsoid),
     HeightField(HeightField),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(CustomShape),
 }
 macro_rules! into_debug_shape {
     ($( $shape:ident ,)*) => (
@@ -40,6 +200,7 @@ macro_rules! into_debug_shape {
                 Self {
                     shape: OutlineableShapeEnum::$shape(shape),
                     density: 4,
+                    reflect_edits: 0,
                 }
             }
         })*
@@ -52,6 +213,7 @@ macro_rules! into_debug_shape_2d {
                 Self {
                     shape: OutlineableShapeEnum::Shape2d(Shape2d::$shape(shape)),
                     density: 4,
+                    reflect_edits: 0,
                 }
             }
         })*
@@ -60,237 +222,403 @@ macro_rules! into_debug_shape_2d {
 #[rustfmt::skip]
 into_debug_shape! {
     Shape2d,    Pyramid,     Extruded, Sphere,
-    HalfSphere, Capsule,     Cuboid,   
-    Cone,       Tetrahedron, Lines,     Cylinder, HeightField,
+    HalfSphere, Capsule,     Cuboid,   RoundedCuboid,
+    Cone,       Tetrahedron, Lines,    Cylinder,
+    Torus,      Ellipsoid,   HeightField,
 }
 #[rustfmt::skip]
 into_debug_shape_2d! {
-    Disc, HalfDisc, QuarterDisc, Rectangle,
-     Triangle, Polygon,
+    Disc, HalfDisc, QuarterDisc, Annulus, Ellipse,
+    Rectangle, RoundedRectangle, Triangle, Polygon,
+}
+impl From<Arc<dyn Outlineable>> for OutlineableShape {
+    fn from(shape: Arc<dyn Outlineable>) -> Self {
+        Self {
+            shape: OutlineableShapeEnum::Custom(CustomShape(shape)),
+            density: 4,
+            reflect_edits: 0,
+        }
+    }
+}
+impl From<Box<dyn Outlineable>> for OutlineableShape {
+    fn from(shape: Box<dyn Outlineable>) -> Self {
+        Self::from(Arc::from(shape))
+    }
 }
 
-fn outline_2d(shape: &Shape2d, density: usize) -> Vec<Vec2> {
-    let segment_rotation = Mat2::from_angle(TAU / 4.0 / density as f32);
-    let circle = |start| iter::successors(Some(start), |v| Some(segment_rotation * *v));
-    let half_circle = |radius: f32| circle(-Vec2::X * radius).take(density * 2 + 1);
-    let full_circle = |radius: f32| circle(-Vec2::X * radius).take(density * 4 + 1);
-    macro_rules! match_2d {
-        ( $input:expr, { $( $shape:ident $binding:tt => $arm:expr ,)* }) => (
-            match $input { $( Shape2d::$shape($shape $binding ) => $arm, )* }
-        )
-    }
-    match_2d!(*shape, {
-        Disc { radius } => full_circle(radius).collect(),
-        HalfDisc { radius } => half_circle(-radius).collect(),
-        QuarterDisc { radius } => circle(Vec2::Y * radius).take(density + 1).collect(),
-        Triangle { a, b, c } => vec![a, b, c, a],
-        Polygon { ref points, ref lines, .. } => lines
-            .iter()
-            .flat_map(|[a, b]| [points[*a], points[*b], Vec2::NAN])
-            .collect(),
-        Rectangle { size } => {
-            let xy = size / 2.0;
-            let a = xy;
-            let b = xy * Vec2::new(1.0, -1.0);
-            vec![a, b, -a, -b, a]
-        },
+// strips![a, b, c] =>
+// let mut strips = LineStrips::new();
+// strips.push_strip(a);
+// strips.push_strip(b);
+// strips.push_strip(c);
+// strips
+macro_rules! strips {
+    ($($strips:expr),* $(,)?) => ({
+        let mut strips = LineStrips::new();
+        $( strips.push_strip($strips); )*
+        strips
     })
 }
 
-impl OutlineableShape {
-    pub(crate) fn outline(&self) -> Vec<Vec3> {
-        // chain_segments![a,b,c,d] =>
-        // a.chain(iter::once(Vec3::NAN))
-        //  .chain(b).chain(iter::once(Vec3::NAN))
-        //  .chain(c).chain(iter::once(Vec3::NAN))
-        //  .chain(d).collect()
-        macro_rules! chain_segments {
-            ($head_iter:expr, $($iters:expr),* $(,)?) => (
-                $head_iter
-                    $(.chain(iter::once(Vec3::NAN)).chain($iters))*
-                    .collect()
-            )
-        }
-        macro_rules! match_3d {
-            ( $input:expr, {
-                Shape2d($first_binding:ident) => $first_arm:expr,
-                $( $shape:ident $binding:tt => $arm:expr ,)*
-            }) => (
-                match $input {
-                    OutlineableShapeEnum::Shape2d(ref $first_binding) => $first_arm,
-                    $( OutlineableShapeEnum::$shape($shape $binding ) => $arm, )*
+pub(crate) fn x3d(v: Vec2) -> Vec3 {
+    Vec3::new(0.0, v.x, v.y)
+}
+pub(crate) fn y3d(v: Vec2) -> Vec3 {
+    Vec3::new(v.x, 0.0, v.y)
+}
+pub(crate) fn z3d(v: Vec2) -> Vec3 {
+    Vec3::new(v.x, v.y, 0.0)
+}
+/// Points on a circle, starting at `start`, rotating counter-clockwise by a
+/// quarter of a circle every `density` points.
+fn circle(start: Vec2, density: usize) -> impl Iterator<Item = Vec2> {
+    let segment_rotation = Mat2::from_angle(TAU / 4.0 / density as f32);
+    iter::successors(Some(start), move |v| Some(segment_rotation * *v))
+}
+fn half_circle(radius: f32, density: usize) -> impl Iterator<Item = Vec2> {
+    circle(-Vec2::X * radius, density).take(density * 2 + 1)
+}
+fn full_circle(radius: f32, density: usize) -> impl Iterator<Item = Vec2> {
+    circle(-Vec2::X * radius, density).take(density * 4 + 1)
+}
+
+// 2d shapes are drawn as their closed `Shape2dBase::boundary`, so that the
+// outline matches the fill and queries. This includes the straight edges of
+// `HalfDisc` and `QuarterDisc`, and `QuarterDisc` is in the +x/+y quadrant.
+macro_rules! outlineable_2d {
+    ($( $shape:ident ),* $(,)?) => (
+        $(impl Outlineable for $shape {
+            fn outline(&self, density: usize) -> LineStrips {
+                let mut strips = LineStrips::new();
+                for strip in self.boundary(density) {
+                    strips.push_strip(strip.into_iter().map(z3d));
                 }
-            )
+                strips
+            }
+            fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+                let fill = self.triangulate(density).into_iter();
+                fill.map(|tri| tri.map(z3d)).collect()
+            }
+        })*
+    )
+}
+outlineable_2d!(
+    Disc,
+    HalfDisc,
+    QuarterDisc,
+    Annulus,
+    Ellipse,
+    Rectangle,
+    RoundedRectangle,
+    Triangle,
+    Polygon,
+    Shape2d
+);
+
+impl<B: Shape2dBase + Send + Sync + 'static> Outlineable for Pyramid<B> {
+    fn outline(&self, density: usize) -> LineStrips {
+        let half_height = Vec3::Y * self.height / 2.0;
+        let bottom = |v: &Vec2| y3d(*v) - half_height;
+        let mut strips = LineStrips::new();
+        for base_strip in self.base.boundary(density) {
+            strips.push_strip(base_strip.iter().map(bottom));
+            strips.push_segments(base_strip.iter().map(|v| [bottom(v), half_height]));
+        }
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl<B: Shape2dBase + Send + Sync + 'static> Outlineable for Extruded<B> {
+    fn outline(&self, density: usize) -> LineStrips {
+        let half_height = Vec3::Y * self.height / 2.0;
+        let bottom = |v: &Vec2| y3d(*v) - half_height;
+        let top = |v: &Vec2| y3d(*v) + half_height;
+        let mut strips = LineStrips::new();
+        for base_strip in self.base.boundary(density) {
+            strips.push_strip(base_strip.iter().map(bottom));
+            strips.push_strip(base_strip.iter().map(top));
+            strips.push_segments(base_strip.iter().map(|v| [bottom(v), top(v)]));
         }
-        let density = self.density;
-        let x3d = |v: Vec2| Vec3::new(0.0, v.x, v.y);
-        let y3d = |v: Vec2| Vec3::new(v.x, 0.0, v.y);
-        let z3d = |v: Vec2| Vec3::new(v.x, v.y, 0.0);
-        let segment_rotation = Mat2::from_angle(TAU / 4.0 / density as f32);
-        let circle = |start| iter::successors(Some(start), |v| Some(segment_rotation * *v));
-        let half_circle = |radius: f32| circle(-Vec2::X * radius).take(density * 2 + 1);
-        let full_circle = |radius: f32| circle(-Vec2::X * radius).take(density * 4 + 1);
-        match_3d!(self.shape, {
-            Shape2d(shape) => outline_2d(shape, density).into_iter().map(z3d).collect(),
-            Pyramid { ref base, height } => {
-                let half_height = Vec3::Y * height / 2.0;
-                let base_shape = outline_2d(base, density);
-                let segment_to_top = |v: &Vec2| [y3d(*v) - half_height, half_height, Vec3::NAN];
-                chain_segments![
-                    base_shape.iter().map(|v| y3d(*v) - half_height),
-                    base_shape.iter().flat_map(segment_to_top),
-                ]
-            },
-            Extruded { ref base, height } => {
-                let half_height = Vec3::Y * height / 2.0;
-                let segment_to_top =
-                    |v: &Vec2| [y3d(*v) - half_height, y3d(*v) + half_height, Vec3::NAN];
-                let base_shape = outline_2d(base, density);
-                chain_segments![
-                    base_shape.iter().map(|v| y3d(*v) - half_height),
-                    base_shape.iter().map(|v| y3d(*v) + half_height),
-                    base_shape.iter().flat_map(segment_to_top),
-                ]
-            },
-            Tetrahedron { a, b, c, d } => vec![a, b, c, a, d, c, Vec3::NAN, d, b],
-            Sphere { radius } => chain_segments![
-                full_circle(radius).map(y3d),
-                full_circle(radius).map(x3d),
-                full_circle(radius).map(z3d),
-            ],
-            HalfSphere { radius } => {
-                let y_half_circle = circle(Vec2::Y * -radius).take(density * 2 + 1);
-                chain_segments![
-                    full_circle(radius).map(y3d),
-                    half_circle(-radius).map(z3d),
-                    y_half_circle.map(x3d),
-                ]
-            },
-            Capsule { radius, segment_height } => {
-                let [x, y, z] = Vec3::AXES;
-                let offset = y * segment_height * 0.5;
-                let top_joint = |extremum| offset + extremum * radius;
-                let bot_joint = |extremum| -offset + extremum * radius;
-                let y_half_circle = |r| circle(Vec2::Y * r).take(density * 2 + 1);
-                // TODO: could be reduced to 3 Segments (instead of 10) by
-                // merging the top & bottom half circles and connecting the
-                // joining longitudes. This would only save uploading to GPU 7 Vec3
-                chain_segments![
-                    // top
-                    y_half_circle(-radius).map(|v| x3d(v) + offset),
-                    full_circle(radius).map(|v| y3d(v) + offset),
-                    half_circle(-radius).map(|v| z3d(v) + offset),
-                    // bottom
-                    y_half_circle(radius).map(|v| x3d(v) - offset),
-                    full_circle(radius).map(|v| y3d(v) - offset),
-                    half_circle(radius).map(|v| z3d(v) - offset),
-                    // Joins top to bottom
-                    [
-                        top_joint(x), bot_joint(x), Vec3::NAN,
-                        top_joint(-x), bot_joint(-x), Vec3::NAN,
-                        top_joint(z), bot_joint(z), Vec3::NAN,
-                        top_joint(-z), bot_joint(-z),
-                    ]
-                ]
-            },
-            Cuboid { size } => {
-                let xy = size.xy() / 2.0;
-                let a = xy;
-                let b = xy * Vec2::new(1.0, -1.0);
-                let c = -a;
-                let d = -b;
-                let hz = size.z / 2.0; // Half-depth
-                let back = |v| z3d(v) - Vec3::Z * hz;
-                let front = |v| z3d(v) + Vec3::Z * hz;
-                vec![
-                    // back
-                    back(a), back(b), back(c), back(d), back(a), front(a), Vec3::NAN,
-                    // front
-                    front(b), front(c), front(d), front(a), front(b), back(b), Vec3::NAN,
-                    // Transversal edges (a and b were sneacked in previous lines)
-                    back(c), front(c), Vec3::NAN, back(d), front(d),
-                ]
-            },
-            // Shape::RoundedRectangle { size, bevel } => { }
-            // Shape::RoundedCuboid { size, bevel } => { }
-            Cone { height, base_radius } => {
-                let half_height = Vec3::Y * height / 2.0;
-                let start = -Vec2::X * base_radius;
-                let circle = full_circle(base_radius);
-                let segment_rotation = Mat2::from_angle(TAU / (density + 1) as f32);
-                let low_res_circle = iter::successors(Some(start), |v| Some(segment_rotation * *v));
-                let segment_to_top = |v| [y3d(v) - half_height, half_height, Vec3::NAN];
-                chain_segments![
-                    circle.map(|v| y3d(v) - half_height),
-                    low_res_circle.take(density + 1).flat_map(segment_to_top),
-                ]
-            },
-            Lines { ref points, ref lines } => lines
-                .iter()
-                .flat_map(|[a, b]| [points[*a], points[*b], Vec3::NAN])
-                .collect(),
-            Cylinder { height, radius } => {
-                let half_height = Vec3::Y * height / 2.0;
-                let start = -Vec2::X * radius;
-                let segment_rotation = Mat2::from_angle(TAU / (density + 1) as f32);
-                let low_res_circle = iter::successors(Some(start), |v| Some(segment_rotation * *v));
-                let segment_to_top = |v| [y3d(v) - half_height, y3d(v) + half_height, Vec3::NAN];
-                chain_segments![
-                    // base
-                    full_circle(radius).map(|v| y3d(v) - half_height),
-                    // top
-                    full_circle(radius).map(|v| y3d(v) + half_height),
-                    // transversals
-                    low_res_circle.take(density + 1).flat_map(segment_to_top),
-                ]
-            },
-            HeightField { ref heights, size } => {
-                let x_len = heights.len();
-                let y_len = heights[0].len();
-                let x_coord = |x| size.x / ((x_len - 1) as f32) * (x as f32) - size.x / 2.0;
-                let y_coord = |y| size.y / ((y_len - 1) as f32) * (y as f32) - size.y / 2.0;
-                let heights = |x: usize| &heights[x];
-                let point_at = |x, y| Vec3::new(x_coord(x), heights(x)[y], y_coord(y));
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Tetrahedron {
+    fn outline(&self, _: usize) -> LineStrips {
+        let Tetrahedron { a, b, c, d } = *self;
+        strips![[a, b, c, a, d, c], [d, b]]
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Sphere {
+    fn outline(&self, density: usize) -> LineStrips {
+        let full_circle = || full_circle(self.radius, density);
+        strips![
+            full_circle().map(y3d),
+            full_circle().map(x3d),
+            full_circle().map(z3d),
+        ]
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for HalfSphere {
+    fn outline(&self, density: usize) -> LineStrips {
+        let radius = self.radius;
+        let y_half_circle = circle(Vec2::Y * -radius, density).take(density * 2 + 1);
+        strips![
+            full_circle(radius, density).map(y3d),
+            half_circle(-radius, density).map(z3d),
+            y_half_circle.map(x3d),
+        ]
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Capsule {
+    fn outline(&self, density: usize) -> LineStrips {
+        let Capsule { radius, segment_height } = *self;
+        let [x, y, z] = Vec3::AXES;
+        let offset = y * segment_height * 0.5;
+        let joint = |extremum| [offset + extremum * radius, -offset + extremum * radius];
+        let y_half_circle = |r| circle(Vec2::Y * r, density).take(density * 2 + 1);
+        let half_circle = |r| half_circle(r, density);
+        let full_circle = || full_circle(radius, density);
+        // TODO: could be reduced to 3 Segments (instead of 10) by
+        // merging the top & bottom half circles and connecting the
+        // joining longitudes. This would only save uploading to GPU 7 Vec3
+        let mut strips = strips![
+            // top
+            y_half_circle(-radius).map(|v| x3d(v) + offset),
+            full_circle().map(|v| y3d(v) + offset),
+            half_circle(-radius).map(|v| z3d(v) + offset),
+            // bottom
+            y_half_circle(radius).map(|v| x3d(v) - offset),
+            full_circle().map(|v| y3d(v) - offset),
+            half_circle(radius).map(|v| z3d(v) - offset),
+        ];
+        // Joins top to bottom
+        strips.push_segments([x, -x, z, -z].map(joint));
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Cuboid {
+    #[rustfmt::skip]
+    fn outline(&self, _: usize) -> LineStrips {
+        let size = self.size;
+        let xy = size.xy() / 2.0;
+        let a = xy;
+        let b = xy * Vec2::new(1.0, -1.0);
+        let c = -a;
+        let d = -b;
+        let hz = size.z / 2.0; // Half-depth
+        let back = |v| z3d(v) - Vec3::Z * hz;
+        let front = |v| z3d(v) + Vec3::Z * hz;
+        strips![
+            // back
+            [back(a), back(b), back(c), back(d), back(a), front(a)],
+            // front
+            [front(b), front(c), front(d), front(a), front(b), back(b)],
+            // Transversal edges (a and b were sneacked in previous lines)
+            [back(c), front(c)],
+            [back(d), front(d)],
+        ]
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for RoundedCuboid {
+    /// Two rounded rectangles per axis: the cross-sections of the cuboid
+    /// across that axis at `±(size / 2 - bevel)`, where its flat faces start
+    /// rounding into the edges.
+    fn outline(&self, density: usize) -> LineStrips {
+        let RoundedCuboid { size, bevel } = *self;
+        let inner = size / 2.0 - bevel;
+        let face_loop = |size| {
+            let boundary = RoundedRectangle { size, bevel }.boundary(density);
+            boundary.into_iter().flatten()
+        };
+        let mut strips = LineStrips::new();
+        for side in [-1.0, 1.0] {
+            strips.push_strip(face_loop(size.xy()).map(|v| z3d(v) + Vec3::Z * inner.z * side));
+            strips.push_strip(face_loop(size.yz()).map(|v| x3d(v) + Vec3::X * inner.x * side));
+            strips.push_strip(face_loop(size.xz()).map(|v| y3d(v) + Vec3::Y * inner.y * side));
+        }
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Cone {
+    fn outline(&self, density: usize) -> LineStrips {
+        let Cone { height, base_radius } = *self;
+        let half_height = Vec3::Y * height / 2.0;
+        let start = -Vec2::X * base_radius;
+        let segment_rotation = Mat2::from_angle(TAU / (density + 1) as f32);
+        let low_res_circle = iter::successors(Some(start), |v| Some(segment_rotation * *v));
+        let segment_to_top = |v| [y3d(v) - half_height, half_height];
+        let mut strips = strips![full_circle(base_radius, density).map(|v| y3d(v) - half_height)];
+        strips.push_segments(low_res_circle.take(density + 1).map(segment_to_top));
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Lines {
+    fn outline(&self, _: usize) -> LineStrips {
+        let points = &self.points;
+        let mut strips = LineStrips::new();
+        strips.push_segments(self.lines.iter().map(|[a, b]| [points[*a], points[*b]]));
+        strips
+    }
+}
+impl Outlineable for Cylinder {
+    fn outline(&self, density: usize) -> LineStrips {
+        let Cylinder { height, radius } = *self;
+        let half_height = Vec3::Y * height / 2.0;
+        let start = -Vec2::X * radius;
+        let segment_rotation = Mat2::from_angle(TAU / (density + 1) as f32);
+        let low_res_circle = iter::successors(Some(start), |v| Some(segment_rotation * *v));
+        let segment_to_top = |v| [y3d(v) - half_height, y3d(v) + half_height];
+        let mut strips = strips![
+            // base
+            full_circle(radius, density).map(|v| y3d(v) - half_height),
+            // top
+            full_circle(radius, density).map(|v| y3d(v) + half_height),
+        ];
+        // transversals
+        strips.push_segments(low_res_circle.take(density + 1).map(segment_to_top));
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Torus {
+    /// Parallels on the top, bottom, inside and outside of the ring, and
+    /// `2 * density` meridians around the tube.
+    fn outline(&self, density: usize) -> LineStrips {
+        let Torus { major_radius, minor_radius } = *self;
+        // A point of the tube's cross-section, `v.x` away from the center of
+        // the tube toward `direction`, at height `v.y`.
+        let on_tube =
+            |direction: Vec2, v: Vec2| y3d(direction * (major_radius + v.x)) + Vec3::Y * v.y;
+        let mut strips = LineStrips::new();
+        for v in circle(Vec2::X * minor_radius, 1).take(4) {
+            let parallel = full_circle(major_radius + v.x, density);
+            strips.push_strip(parallel.map(|p| y3d(p) + Vec3::Y * v.y));
+        }
+        let meridians = circle(Vec2::X, density).step_by(2).take(density * 2);
+        for direction in meridians {
+            strips.push_strip(full_circle(minor_radius, density).map(|v| on_tube(direction, v)));
+        }
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for Ellipsoid {
+    /// The ellipses in the XY, YZ and XZ planes.
+    fn outline(&self, density: usize) -> LineStrips {
+        let ellipse = |to_3d: fn(Vec2) -> Vec3| {
+            full_circle(1.0, density).map(move |v| to_3d(v) * self.half_axes)
+        };
+        strips![ellipse(y3d), ellipse(x3d), ellipse(z3d)]
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+impl Outlineable for HeightField {
+    fn outline(&self, _: usize) -> LineStrips {
+        let HeightField { ref heights, size } = *self;
+        let x_len = heights.len();
+        let y_len = heights[0].len();
+        let x_coord = |x| size.x / ((x_len - 1) as f32) * (x as f32) - size.x / 2.0;
+        let y_coord = |y| size.y / ((y_len - 1) as f32) * (y as f32) - size.y / 2.0;
+        let heights = |x: usize| &heights[x];
+        let point_at = |x, y| Vec3::new(x_coord(x), heights(x)[y], y_coord(y));
 
-                // builds two segments, one containing the "zigzag" pattern
-                // between each columns, going upward then downward, up, down etc.
-                // This segment contains all the horizontal and diagonal lines
-                let mut zig_zags = Vec::with_capacity((x_len - 1) * (2 * y_len + 1));
-                // And one that contains the columns. In a single segment
-                // thanks to going all the way up, then going one step to the
-                // right then all the way down, repeating.
-                let mut y_axises =
-                    Vec::with_capacity((1 + y_len) * x_len + zig_zags.capacity() + 1);
-                let mut x = 0;
-                let mut y = 0;
-                // This loop increments y until it reaches the upper bound,
-                // then increments x, then decrements y until it reaches the
-                // lower bound, then increment x, then increment y until etc.
-                loop {
-                    let y_len = y_len as isize;
-                    if x != x_len - 1 {
-                        zig_zags.push(point_at(x, y));
-                        zig_zags.push(point_at(x + 1, y));
-                    }
-                    y_axises.push(point_at(x, y));
+        // builds two strips, one containing the "zigzag" pattern
+        // between each columns, going upward then downward, up, down etc.
+        // This strip contains all the horizontal and diagonal lines
+        let mut zig_zags = Vec::with_capacity((x_len - 1) * 2 * y_len);
+        // And one that contains the columns. In a single strip per column
+        // thanks to going all the way up, then going one step to the
+        // right then all the way down, repeating.
+        let mut strips = LineStrips::new();
+        let mut column = Vec::with_capacity(y_len);
+        let mut x = 0;
+        let mut y = 0;
+        // This loop increments y until it reaches the upper bound,
+        // then increments x, then decrements y until it reaches the
+        // lower bound, then increment x, then increment y until etc.
+        loop {
+            let y_len = y_len as isize;
+            if x != x_len - 1 {
+                zig_zags.push(point_at(x, y));
+                zig_zags.push(point_at(x + 1, y));
+            }
+            column.push(point_at(x, y));
 
-                    let y_direction = if x % 2 == 0 { 1 } else { -1 };
-                    let iy = y as isize + y_direction;
-                    let y_out_of_bound = iy >= y_len || iy < 0;
+            let y_direction = if x % 2 == 0 { 1 } else { -1 };
+            let iy = y as isize + y_direction;
+            let y_out_of_bound = iy >= y_len || iy < 0;
 
-                    if y_out_of_bound && x >= x_len - 1 {
-                        break;
-                    } else if y_out_of_bound {
-                        y_axises.push(Vec3::NAN);
-                        x += 1;
-                    } else {
-                        y = iy as usize;
-                    }
-                }
-                y_axises.push(Vec3::NAN);
-                y_axises.extend(zig_zags);
-                y_axises
-            },
-        })
+            if y_out_of_bound && x >= x_len - 1 {
+                break;
+            } else if y_out_of_bound {
+                strips.push_strip(column.drain(..));
+                x += 1;
+            } else {
+                y = iy as usize;
+            }
+        }
+        strips.push_strip(column);
+        strips.push_strip(zig_zags);
+        strips
+    }
+    fn fill(&self, density: usize) -> Vec<[Vec3; 3]> {
+        Fill::fill(self, density)
+    }
+}
+
+impl OutlineableShape {
+    /// The line strips of this shape's outline, see [`Outlineable::outline`].
+    pub fn outline(&self) -> LineStrips {
+        // `density` is not clamped when set through deserialization or
+        // reflection.
+        self.as_outlineable().outline(self.density.max(1))
+    }
+
+    /// The outline as a [`PrimitiveTopology::LineList`] mesh.
+    ///
+    /// Used when the outline is drawn on top of everything, since polylines
+    /// do not let us disable depth checking.
+    pub(crate) fn outline_mesh(&self) -> Mesh {
+        let strips = self.outline();
+        let positions: Vec<[f32; 3]> = strips.segments().flatten().map(|v| v.to_array()).collect();
+        let normals = vec![[0.0_f32, 1.0, 0.0]; positions.len()];
+        let uvs = vec![[0.0_f32; 2]; positions.len()];
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh
     }
 }
@@ -0,0 +1,280 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_polyline::prelude::*;
+
+use crate::material::{OutlineStyle, PolylineAssets};
+use crate::outline::OutlineableShape;
+
+struct PaintedShape {
+    shape: OutlineableShape,
+    transform: Transform,
+    material: PolylineMaterial,
+    /// Seconds left before the shape stops being drawn, `None` if it should
+    /// only be drawn this frame.
+    remaining: Option<f32>,
+}
+
+/// Shapes to draw this frame, filled by [`ShapePainter`].
+pub struct PaintQueue {
+    shapes: Vec<PaintedShape>,
+    width: f32,
+}
+impl Default for PaintQueue {
+    fn default() -> Self {
+        PaintQueue { shapes: Vec::new(), width: 1.0 }
+    }
+}
+impl PaintQueue {
+    /// Forget the shapes which time ran out after `delta` seconds, and the
+    /// ones only drawn for this frame.
+    fn advance(&mut self, delta: f32) {
+        for painted in &mut self.shapes {
+            if let Some(remaining) = &mut painted.remaining {
+                *remaining -= delta;
+            }
+        }
+        self.shapes
+            .retain(|painted| matches!(painted.remaining, Some(time) if time > 0.0));
+    }
+}
+
+/// Marks entites spawned by [`paint_shapes`].
+#[derive(Component)]
+pub(crate) struct PaintedLines;
+
+/// Draw shape outlines in immediate mode, without spawning entities.
+///
+/// Outlines drawn with [`ShapePainter::outline`] are only visible for the
+/// current frame, so you need to draw them again each frame.
+/// [`ShapePainter::outline_for`] keeps the outline around for the given
+/// amount of seconds.
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+/// use bevy_cool_shapes_render::prelude::*;
+///
+/// fn show_range(mut painter: ShapePainter, towers: Query<&Transform>) {
+///     for transform in towers.iter() {
+///         painter.outline(Cylinder { radius: 5.0, height: 1.0 }, *transform, Color::RED);
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct ShapePainter<'w, 's> {
+    queue: ResMut<'w, PaintQueue>,
+    #[system_param(ignore)]
+    _lifetime: PhantomData<&'s ()>,
+}
+impl<'w, 's> ShapePainter<'w, 's> {
+    fn paint(
+        &mut self,
+        shape: OutlineableShape,
+        transform: Transform,
+        color: Color,
+        remaining: Option<f32>,
+    ) {
+        let width = self.queue.width;
+        let material = PolylineMaterial { width, color, perspective: true };
+        let painted = PaintedShape { shape, transform, material, remaining };
+        self.queue.shapes.push(painted);
+    }
+    /// Draw the outline of `shape` for the current frame.
+    pub fn outline(
+        &mut self,
+        shape: impl Into<OutlineableShape>,
+        transform: Transform,
+        color: Color,
+    ) {
+        self.paint(shape.into(), transform, color, None);
+    }
+    /// Draw the outline of `shape` for the next `seconds` seconds.
+    pub fn outline_for(
+        &mut self,
+        seconds: f32,
+        shape: impl Into<OutlineableShape>,
+        transform: Transform,
+        color: Color,
+    ) {
+        self.paint(shape.into(), transform, color, Some(seconds));
+    }
+    /// Set the line width of the outlines drawn after this call.
+    pub fn set_width(&mut self, width: f32) {
+        self.queue.width = width;
+    }
+}
+
+type PooledLines<'a> = (
+    &'a mut Transform,
+    &'a mut Visibility,
+    &'a Handle<Polyline>,
+    &'a mut Handle<PolylineMaterial>,
+);
+
+/// Draw the shapes of [`PaintQueue`], reusing the polylines of the previous
+/// frames.
+pub(crate) fn paint_shapes(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut queue: ResMut<PaintQueue>,
+    mut pool: Local<Vec<Entity>>,
+    mut lines: Query<PooledLines, With<PaintedLines>>,
+    mut assets: PolylineAssets,
+) {
+    let mut pooled = pool.iter();
+    let mut spawned = Vec::new();
+    for painted in &queue.shapes {
+        if let Err(error) = painted.shape.validate() {
+            warn!("Not painting an invalid shape: {}", error);
+            continue;
+        }
+        let vertices = painted.shape.outline().polyline_vertices();
+        let style = OutlineStyle::from(&painted.material);
+        let material = assets.material(style, painted.material);
+        let pooled_lines = pooled.next().and_then(|entity| lines.get_mut(*entity).ok());
+        if let Some((mut transform, mut visibility, polyline, mut old_material)) = pooled_lines {
+            *transform = painted.transform;
+            visibility.is_visible = true;
+            if let Some(polyline) = assets.polylines.get_mut(polyline) {
+                polyline.vertices = vertices;
+            }
+            if *old_material != material {
+                *old_material = material;
+            }
+        } else {
+            // The entity is spawned after transform propagation, so we set
+            // its `GlobalTransform` ourselves to draw it in place this frame.
+            let bundle = PolylineBundle {
+                polyline: assets.polylines.add(Polyline { vertices }),
+                material,
+                transform: painted.transform,
+                global_transform: painted.transform.into(),
+                ..default()
+            };
+            spawned.push(cmds.spawn_bundle(bundle).insert(PaintedLines).id());
+        }
+    }
+    for entity in pooled {
+        if let Ok((_, mut visibility, ..)) = lines.get_mut(*entity) {
+            visibility.is_visible = false;
+        }
+    }
+    pool.extend(spawned);
+    queue.advance(time.delta_seconds());
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+    use bevy::core::CorePlugin;
+    use bevy::ecs::system::SystemState;
+
+    use super::*;
+    use crate::material::OutlineMaterials;
+    use bevy_cool_shapes::Sphere;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Polyline>()
+            .add_asset::<PolylineMaterial>()
+            .init_resource::<OutlineMaterials>()
+            .init_resource::<PaintQueue>()
+            .add_system(paint_shapes);
+        app
+    }
+    fn paint(app: &mut App, draw: impl FnOnce(&mut ShapePainter)) {
+        let mut state = SystemState::<ShapePainter>::new(&mut app.world);
+        draw(&mut state.get_mut(&mut app.world));
+    }
+    fn painted_lines(app: &mut App) -> Vec<(GlobalTransform, bool, Handle<PolylineMaterial>)> {
+        let mut query = app
+            .world
+            .query_filtered::<(&GlobalTransform, &Visibility, &Handle<PolylineMaterial>), With<PaintedLines>>();
+        let lines = query.iter(&app.world);
+        lines
+            .map(|(transform, visibility, material)| {
+                (*transform, visibility.is_visible, material.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new_lines_are_placed_on_first_frame() {
+        let mut app = app();
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        paint(&mut app, |painter| {
+            painter.outline(Sphere { radius: 1.0 }, transform, Color::RED);
+        });
+        app.update();
+        let lines = painted_lines(&mut app);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].0.translation, transform.translation);
+    }
+
+    #[test]
+    fn same_style_shares_material() {
+        let mut app = app();
+        for _ in 0..2 {
+            paint(&mut app, |painter| {
+                painter.outline(Sphere { radius: 1.0 }, Transform::identity(), Color::RED);
+                painter.outline(Sphere { radius: 2.0 }, Transform::identity(), Color::RED);
+            });
+            app.update();
+        }
+        let lines = painted_lines(&mut app);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].2, lines[1].2);
+        assert_eq!(app.world.resource::<Assets<PolylineMaterial>>().len(), 1);
+
+        paint(&mut app, |painter| {
+            painter.set_width(3.0);
+            painter.outline(Sphere { radius: 1.0 }, Transform::identity(), Color::RED);
+        });
+        app.update();
+        let lines = painted_lines(&mut app);
+        let materials = app.world.resource::<Assets<PolylineMaterial>>();
+        let visible = lines.iter().find(|(_, visible, _)| *visible).unwrap();
+        assert_eq!(materials.get(&visible.2).unwrap().width, 3.0);
+    }
+
+    #[test]
+    fn pooled_lines_hidden_when_not_painted() {
+        let mut app = app();
+        paint(&mut app, |painter| {
+            painter.outline(Sphere { radius: 1.0 }, Transform::identity(), Color::RED);
+            painter.outline_for(
+                10.0,
+                Sphere { radius: 1.0 },
+                Transform::identity(),
+                Color::RED,
+            );
+        });
+        app.update();
+        let visible = |app: &mut App| painted_lines(app).iter().filter(|l| l.1).count();
+        assert_eq!(visible(&mut app), 2);
+        app.update();
+        assert_eq!(visible(&mut app), 1);
+        assert_eq!(painted_lines(&mut app).len(), 2);
+    }
+
+    #[test]
+    fn outlines_expire() {
+        let mut queue = PaintQueue::default();
+        let painted = |remaining| PaintedShape {
+            shape: Sphere { radius: 1.0 }.into(),
+            transform: Transform::identity(),
+            material: PolylineMaterial::default(),
+            remaining,
+        };
+        queue.shapes = vec![painted(None), painted(Some(1.0)), painted(Some(2.0))];
+        queue.advance(0.5);
+        assert_eq!(queue.shapes.len(), 2);
+        queue.advance(0.5);
+        assert_eq!(queue.shapes.len(), 1);
+        queue.advance(1.5);
+        assert!(queue.shapes.is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+//! [`Reflect`] for [`OutlineableShape`].
+//!
+//! Like `Shape2d` in `bevy_cool_shapes`, the shape is reflected as a field
+//! named after its variant, next to the `density` field. So a designer can
+//! edit `shape.capsule.radius` on a [`ShapeOutline`] in an inspector.
+//!
+//! All mutable accesses increment [`OutlineableShape::reflect_edits`], so
+//! that the outline is rebuilt.
+//!
+//! [`ShapeOutline`]: crate::render::ShapeOutline
+
+use bevy::reflect::{
+    serde::Serializable, struct_partial_eq, DynamicStruct, FieldIter, FromReflect,
+    GetTypeRegistration, Reflect, ReflectMut, ReflectRef, Struct, TypeRegistration,
+};
+use bevy_cool_shapes::*;
+
+use crate::outline::{OutlineableShape, OutlineableShapeEnum};
+
+macro_rules! shape_variants {
+    ($( $variant:ident => $field:literal ,)*) => {
+        impl OutlineableShapeEnum {
+            /// The name of the field of the current variant, and its value.
+            ///
+            /// `None` for custom shapes, which can't be reflected.
+            fn variant(&self) -> Option<(&'static str, &dyn Reflect)> {
+                match self {
+                    $(OutlineableShapeEnum::$variant(shape) => Some(($field, shape)),)*
+                    OutlineableShapeEnum::Custom(_) => None,
+                }
+            }
+            fn variant_mut(&mut self) -> Option<(&'static str, &mut dyn Reflect)> {
+                match self {
+                    $(OutlineableShapeEnum::$variant(shape) => Some(($field, shape)),)*
+                    OutlineableShapeEnum::Custom(_) => None,
+                }
+            }
+            /// The variant which field is `name`, built from `value`.
+            fn from_variant(name: &str, value: &dyn Reflect) -> Option<Self> {
+                match name {
+                    $($field => $variant::from_reflect(value).map(OutlineableShapeEnum::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+shape_variants! {
+    Shape2d => "shape_2d",
+    Pyramid => "pyramid",
+    Extruded => "extruded",
+    Sphere => "sphere",
+    HalfSphere => "half_sphere",
+    Capsule => "capsule",
+    Cuboid => "cuboid",
+    RoundedCuboid => "rounded_cuboid",
+    Cone => "cone",
+    Tetrahedron => "tetrahedron",
+    Lines => "lines",
+    Cylinder => "cylinder",
+    Torus => "torus",
+    Ellipsoid => "ellipsoid",
+    HeightField => "height_field",
+}
+
+impl OutlineableShape {
+    /// `self` was mutably accessed, the shape may change.
+    fn edited(&mut self) -> &mut Self {
+        self.reflect_edits = self.reflect_edits.wrapping_add(1);
+        self
+    }
+}
+impl Struct for OutlineableShape {
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        match (name, self.shape.variant()) {
+            ("density", _) => Some(&self.density),
+            (name, Some((variant, value))) if name == variant => Some(value),
+            _ => None,
+        }
+    }
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        let this = self.edited();
+        match (name, this.shape.variant_mut()) {
+            ("density", _) => Some(&mut this.density),
+            (name, Some((variant, value))) if name == variant => Some(value),
+            _ => None,
+        }
+    }
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+        match index {
+            0 => Some(&self.density),
+            1 => self.shape.variant().map(|(_, value)| value),
+            _ => None,
+        }
+    }
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        let this = self.edited();
+        match index {
+            0 => Some(&mut this.density),
+            1 => this.shape.variant_mut().map(|(_, value)| value),
+            _ => None,
+        }
+    }
+    fn name_at(&self, index: usize) -> Option<&str> {
+        match index {
+            0 => Some("density"),
+            1 => self.shape.variant().map(|(name, _)| name),
+            _ => None,
+        }
+    }
+    fn field_len(&self) -> usize {
+        match self.shape.variant() {
+            Some(_) => 2,
+            None => 1,
+        }
+    }
+    fn iter_fields(&self) -> FieldIter<'_> {
+        FieldIter::new(self)
+    }
+    fn clone_dynamic(&self) -> DynamicStruct {
+        let mut dynamic = DynamicStruct::default();
+        dynamic.set_name(self.type_name().to_string());
+        dynamic.insert("density", self.density);
+        if let Some((variant, value)) = self.shape.variant() {
+            dynamic.insert_boxed(variant, value.clone_value());
+        }
+        dynamic
+    }
+}
+// SAFE: any and any_mut both return self
+unsafe impl Reflect for OutlineableShape {
+    fn type_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+    fn any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+    fn clone_value(&self) -> Box<dyn Reflect> {
+        Box::new(self.clone_dynamic())
+    }
+    fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        let reflect_edits = self.reflect_edits;
+        *self = value.take()?;
+        self.reflect_edits = reflect_edits.wrapping_add(1);
+        Ok(())
+    }
+    /// Apply the fields of `value`, switching to the shape of `value` if it
+    /// is of a different variant.
+    ///
+    /// `value` is ignored if it is not a struct.
+    fn apply(&mut self, value: &dyn Reflect) {
+        let value = match value.reflect_ref() {
+            ReflectRef::Struct(value) => value,
+            _ => return,
+        };
+        for (i, field) in value.iter_fields().enumerate() {
+            let name = value.name_at(i).unwrap();
+            if let Some(current) = self.field_mut(name) {
+                current.apply(field);
+            } else if let Some(shape) = OutlineableShapeEnum::from_variant(name, field) {
+                self.shape = shape;
+            }
+        }
+    }
+    fn reflect_ref(&self) -> ReflectRef<'_> {
+        ReflectRef::Struct(self)
+    }
+    fn reflect_mut(&mut self) -> ReflectMut<'_> {
+        ReflectMut::Struct(self)
+    }
+    fn serializable(&self) -> Option<Serializable<'_>> {
+        None
+    }
+    fn reflect_hash(&self) -> Option<u64> {
+        None
+    }
+    fn reflect_partial_eq(&self, value: &dyn Reflect) -> Option<bool> {
+        struct_partial_eq(self, value)
+    }
+}
+impl FromReflect for OutlineableShape {
+    fn from_reflect(reflect: &dyn Reflect) -> Option<Self> {
+        let value = match reflect.reflect_ref() {
+            ReflectRef::Struct(value) => value,
+            _ => return None,
+        };
+        let density = usize::from_reflect(value.field("density")?)?;
+        let shape = value
+            .iter_fields()
+            .enumerate()
+            .find_map(|(i, field)| OutlineableShapeEnum::from_variant(value.name_at(i)?, field))?;
+        Some(OutlineableShape { density, shape, reflect_edits: 0 })
+    }
+}
+impl GetTypeRegistration for OutlineableShape {
+    fn get_type_registration() -> TypeRegistration {
+        TypeRegistration::of::<Self>()
+    }
+}
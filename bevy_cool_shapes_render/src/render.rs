@@ -1,17 +1,34 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
+use crate::batch::BatchedOutline;
+use crate::material::{OutlineMaterials, OutlineStyle, ShapeMaterial, ShapeMaterials, ShapeStyle};
 use crate::outline::OutlineableShape;
+use crate::RenderableShapesSettings;
 use bevy_polyline::prelude::*;
 
 #[derive(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
 pub struct ShapeOutline {
     shape: OutlineableShape,
     color: Color,
     // TODO: actually update this when this is merged:
     // https://github.com/ForesightMiningSoftwareCorporation/bevy_polyline/pull/26
-    #[allow(unused)]
     depth_bias: f32,
+    /// Ignored when `depth_check` is `false`, see [`ShapeOutline::width`].
     width: f32,
+    depth_check: bool,
+    /// Incremented each time `shape` may have changed, so that we only
+    /// rebuild the lines when necessary.
+    ///
+    /// Edits through reflection are counted in `shape`, see
+    /// [`ShapeOutline::shape_version()`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    shape_version: u32,
 }
 impl Default for ShapeOutline {
     fn default() -> Self {
@@ -20,17 +37,190 @@ impl Default for ShapeOutline {
             color: Color::YELLOW,
             depth_bias: 0.0,
             width: 1.0,
+            depth_check: true,
+            shape_version: 0,
+        }
+    }
+}
+impl ShapeOutline {
+    /// Whether the outline is hidden by geometry in front of it.
+    ///
+    /// With `false`, the outline is always drawn on top of everything else.
+    /// Note that in this mode, the lines are 1 pixel wide, `width` is ignored.
+    pub fn with_depth_check(mut self, depth_check: bool) -> Self {
+        self.depth_check = depth_check;
+        self
+    }
+    pub fn depth_check(&self) -> bool {
+        self.depth_check
+    }
+    /// See [`ShapeOutline::with_depth_check`].
+    pub fn set_depth_check(&mut self, depth_check: bool) {
+        self.depth_check = depth_check;
+    }
+    pub fn color(&self) -> Color {
+        self.color
+    }
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+    /// The width of the lines, in pixels.
+    ///
+    /// Without depth checking, the lines are always 1 pixel wide, see
+    /// [`ShapeOutline::with_depth_check`].
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    /// See [`ShapeOutline::width`].
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width;
+    }
+    /// How much the lines are pulled toward the camera, to avoid clipping
+    /// into the surfaces they outline.
+    ///
+    /// This is stored but not used for rendering yet, `bevy_polyline` doesn't
+    /// support depth bias.
+    pub fn depth_bias(&self) -> f32 {
+        self.depth_bias
+    }
+    /// See [`ShapeOutline::depth_bias`].
+    pub fn set_depth_bias(&mut self, depth_bias: f32) {
+        self.depth_bias = depth_bias;
+    }
+    pub fn shape(&self) -> &OutlineableShape {
+        &self.shape
+    }
+    /// Replace the outlined shape, including its density.
+    pub fn set_shape(&mut self, shape: impl Into<OutlineableShape>) {
+        *self.shape_mut() = shape.into();
+    }
+    pub fn shape_mut(&mut self) -> &mut OutlineableShape {
+        // Move the reflection edits out of `shape`, so that they are not
+        // lost if `shape` is replaced.
+        self.shape_version = self.shape_version().wrapping_add(1);
+        self.shape.reflect_edits = 0;
+        &mut self.shape
+    }
+    /// Changes each time `shape` may have changed, either through
+    /// [`ShapeOutline::shape_mut`] or through reflection.
+    pub(crate) fn shape_version(&self) -> u32 {
+        self.shape_version.wrapping_add(self.shape.reflect_edits)
+    }
+    pub fn density(&self) -> usize {
+        self.shape.density()
+    }
+    /// See [`OutlineableShape::set_density`].
+    pub fn set_density(&mut self, density: usize) {
+        self.shape_mut().set_density(density);
+    }
+    pub(crate) fn style(&self) -> OutlineStyle {
+        OutlineStyle::new(self.color, self.width, true, self.depth_bias)
+    }
+    pub(crate) fn polyline_material(&self) -> PolylineMaterial {
+        PolylineMaterial {
+            width: self.width,
+            color: self.color,
+            perspective: true,
         }
     }
+    fn on_top_material(&self) -> ShapeMaterial {
+        ShapeMaterial { color: self.color, depth_check: false }
+    }
+}
+
+/// The surfaces of a shape, rendered as a solid mesh.
+///
+/// Use a `color` with an alpha lower than `1.0` to get a translucent volume.
+#[derive(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect), reflect(Component))]
+pub struct ShapeFill {
+    shape: OutlineableShape,
+    color: Color,
+    depth_check: bool,
+    /// See [`ShapeOutline::shape_version()`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    shape_version: u32,
+}
+impl Default for ShapeFill {
+    fn default() -> Self {
+        ShapeFill {
+            shape: OutlineableShape::default(),
+            color: Color::rgba(1.0, 1.0, 0.0, 0.3),
+            depth_check: true,
+            shape_version: 0,
+        }
+    }
+}
+impl ShapeFill {
+    /// Whether the surfaces are hidden by geometry in front of them.
+    ///
+    /// With `false`, the fill is always drawn on top of everything else.
+    pub fn with_depth_check(mut self, depth_check: bool) -> Self {
+        self.depth_check = depth_check;
+        self
+    }
+    pub fn depth_check(&self) -> bool {
+        self.depth_check
+    }
+    /// See [`ShapeFill::with_depth_check`].
+    pub fn set_depth_check(&mut self, depth_check: bool) {
+        self.depth_check = depth_check;
+    }
+    pub fn color(&self) -> Color {
+        self.color
+    }
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+    pub fn shape(&self) -> &OutlineableShape {
+        &self.shape
+    }
+    /// Replace the filled shape, including its density.
+    pub fn set_shape(&mut self, shape: impl Into<OutlineableShape>) {
+        *self.shape_mut() = shape.into();
+    }
+    pub fn shape_mut(&mut self) -> &mut OutlineableShape {
+        // See `ShapeOutline::shape_mut`.
+        self.shape_version = self.shape_version().wrapping_add(1);
+        self.shape.reflect_edits = 0;
+        &mut self.shape
+    }
+    /// See [`ShapeOutline::shape_version()`].
+    pub(crate) fn shape_version(&self) -> u32 {
+        self.shape_version.wrapping_add(self.shape.reflect_edits)
+    }
+    fn material(&self) -> ShapeMaterial {
+        ShapeMaterial { color: self.color, depth_check: self.depth_check }
+    }
 }
 
 /// Easy extension methods on [`Shape`] to quickly create a [`DebugShapeOutline`].
 pub trait IntoOutline {
+    /// See [`ShapeOutline::width`] for `width` and
+    /// [`ShapeOutline::depth_bias`] for `bias`.
     fn lines(self, color: Color, width: f32, bias: f32) -> ShapeOutline;
+    fn fill(self, color: Color) -> ShapeFill;
 }
 impl IntoOutline for OutlineableShape {
     fn lines(self, color: Color, width: f32, bias: f32) -> ShapeOutline {
-        ShapeOutline { shape: self, color, width, depth_bias: bias }
+        ShapeOutline {
+            shape: self,
+            color,
+            width,
+            depth_bias: bias,
+            depth_check: true,
+            shape_version: 0,
+        }
+    }
+    fn fill(self, color: Color) -> ShapeFill {
+        ShapeFill {
+            shape: self,
+            color,
+            depth_check: true,
+            shape_version: 0,
+        }
     }
 }
 
@@ -38,60 +228,292 @@ impl IntoOutline for OutlineableShape {
 ///
 /// This let us recognize which child we care about and need to update.
 #[derive(Component)]
-pub(crate) struct LineMesh;
+pub(crate) struct LineMesh {
+    /// The [`ShapeOutline::shape_version()`] the lines were built from.
+    shape_version: u32,
+}
+
+/// Marks entites spawned by [`insert_fill`].
+#[derive(Component)]
+pub(crate) struct FillMesh {
+    /// The [`ShapeFill::shape_version()`] the mesh was built from.
+    shape_version: u32,
+}
+
+/// The assets used by the entities rendering the shapes.
+#[derive(SystemParam)]
+pub(crate) struct ShapeAssets<'w, 's> {
+    polylines: ResMut<'w, Assets<Polyline>>,
+    poly_mats: ResMut<'w, Assets<PolylineMaterial>>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    shape_mats: ResMut<'w, Assets<ShapeMaterial>>,
+    outline_materials: ResMut<'w, OutlineMaterials>,
+    shape_materials: ResMut<'w, ShapeMaterials>,
+    settings: Res<'w, RenderableShapesSettings>,
+    #[system_param(ignore)]
+    _lifetime: PhantomData<&'s ()>,
+}
+impl<'w, 's> ShapeAssets<'w, 's> {
+    fn polyline_material(&mut self, outline: &ShapeOutline) -> Handle<PolylineMaterial> {
+        let material = || outline.polyline_material();
+        self.outline_materials
+            .get(outline.style(), &mut self.poly_mats, material)
+    }
+    fn shape_material(&mut self, material: ShapeMaterial) -> Handle<ShapeMaterial> {
+        let style = ShapeStyle::from(&material);
+        self.shape_materials
+            .get(style, &mut self.shape_mats, || material)
+    }
+    /// Add the lines of `outline` to `entity`, either as a child entity or as
+    /// a [`BatchedOutline`] in batched mode.
+    ///
+    /// The child entity starts with the `visibility` of `entity`, since
+    /// [`update_outlines_visibility`] only runs when it changes.
+    ///
+    /// Invalid shapes are skipped with a warning.
+    fn spawn_lines(
+        &mut self,
+        cmds: &mut Commands,
+        entity: Entity,
+        outline: &ShapeOutline,
+        visibility: Option<&Visibility>,
+    ) {
+        if let Err(error) = outline.shape.validate() {
+            warn!(
+                "Not rendering the invalid outline of {:?}: {}",
+                entity, error
+            );
+            return;
+        }
+        let shape_version = outline.shape_version();
+        let line_mesh = LineMesh { shape_version };
+        let aabb = outline.shape.render_aabb();
+        if outline.depth_check && self.settings.batch_outlines {
+            let strips = outline.shape.outline();
+            cmds.entity(entity)
+                .insert(BatchedOutline { strips, shape_version });
+        } else if outline.depth_check {
+            let vertices = outline.shape.outline().polyline_vertices();
+            let bundle = PolylineBundle {
+                polyline: self.polylines.add(Polyline { vertices }),
+                material: self.polyline_material(outline),
+                visibility: visibility.cloned().unwrap_or_default(),
+                ..default()
+            };
+            cmds.entity(entity).with_children(|cmds| {
+                cmds.spawn_bundle(bundle).insert_bundle((line_mesh, aabb));
+            });
+        } else {
+            let bundle = MaterialMeshBundle {
+                mesh: self.meshes.add(outline.shape.outline_mesh()),
+                material: self.shape_material(outline.on_top_material()),
+                visibility: visibility.cloned().unwrap_or_default(),
+                ..default()
+            };
+            cmds.entity(entity).with_children(|cmds| {
+                cmds.spawn_bundle(bundle).insert_bundle((line_mesh, aabb));
+            });
+        }
+    }
+    /// Add the surfaces of `fill` as a child of `entity`, see
+    /// [`Self::spawn_lines`].
+    ///
+    /// Invalid shapes are skipped with a warning.
+    fn spawn_fill(
+        &mut self,
+        cmds: &mut Commands,
+        entity: Entity,
+        fill: &ShapeFill,
+        visibility: Option<&Visibility>,
+    ) {
+        if let Err(error) = fill.shape.validate() {
+            warn!("Not rendering the invalid fill of {:?}: {}", entity, error);
+            return;
+        }
+        let bundle = MaterialMeshBundle {
+            mesh: self.meshes.add(fill.shape.fill_mesh()),
+            material: self.shape_material(fill.material()),
+            visibility: visibility.cloned().unwrap_or_default(),
+            ..default()
+        };
+        let fill_mesh = FillMesh { shape_version: fill.shape_version() };
+        let aabb = fill.shape.render_aabb();
+        cmds.entity(entity).with_children(|cmds| {
+            cmds.spawn_bundle(bundle).insert_bundle((fill_mesh, aabb));
+        });
+    }
+}
 
 // How this works: Create many children to the Entity with a DebugShapeOutline
 // component, each one a Polyline or a simple StandardMaterial with opacity
 pub(crate) fn insert_outline(
     mut cmds: Commands,
-    query: Query<(Entity, &ShapeOutline), Added<ShapeOutline>>,
-    mut poly_mats: ResMut<Assets<PolylineMaterial>>,
-    mut polylines: ResMut<Assets<Polyline>>,
+    query: Query<(Entity, &ShapeOutline, Option<&Visibility>), Added<ShapeOutline>>,
+    mut assets: ShapeAssets,
 ) {
-    for (entity, debug) in query.iter() {
-        let material = poly_mats.add(PolylineMaterial {
-            width: debug.width,
-            color: debug.color,
-            perspective: true,
-            // depth_bias: debug.depth_bias,
-        });
-        let vertices = debug.shape.outline();
-        cmds.entity(entity).with_children(|cmds| {
-            let bundle = PolylineBundle {
-                polyline: polylines.add(Polyline { vertices }),
-                material,
-                ..default()
-            };
-            cmds.spawn_bundle(bundle).insert(LineMesh);
-        });
+    for (entity, debug, visibility) in query.iter() {
+        assets.spawn_lines(&mut cmds, entity, debug, visibility);
     }
 }
+type ChangedOutline<'a> = (
+    Entity,
+    Option<&'a Children>,
+    Option<&'a mut BatchedOutline>,
+    ChangeTrackers<ShapeOutline>,
+    &'a ShapeOutline,
+    Option<&'a Visibility>,
+);
+/// Update the line entities, only rebuilding the vertices when the shape
+/// changed.
+///
+/// Invalid shapes are skipped with a warning, their lines are spawned once
+/// they become valid.
 pub(crate) fn update_outline(
-    mut lines: Query<(&mut Handle<Polyline>, &mut Handle<PolylineMaterial>), With<LineMesh>>,
-    mut poly_mats: ResMut<Assets<PolylineMaterial>>,
-    mut polylines: ResMut<Assets<Polyline>>,
-    shapes: Query<(&Children, &ShapeOutline), Changed<ShapeOutline>>,
+    mut cmds: Commands,
+    mut line_meshes: Query<&mut LineMesh>,
+    mut lines: Query<(&Handle<Polyline>, &mut Handle<PolylineMaterial>), With<LineMesh>>,
+    mut on_top_lines: Query<(&Handle<Mesh>, &mut Handle<ShapeMaterial>), With<LineMesh>>,
+    mut assets: ShapeAssets,
+    mut shapes: Query<ChangedOutline, Changed<ShapeOutline>>,
 ) {
-    for (children, debug) in shapes.iter() {
-        for child in children.iter() {
-            if let Ok((mut polyline, mut poly_mat)) = lines.get_mut(*child) {
-                *poly_mat = poly_mats.add(PolylineMaterial {
-                    width: debug.width,
-                    color: debug.color,
-                    perspective: true,
-                    // depth_bias: debug.depth_bias,
-                });
-                let vertices = debug.shape.outline();
-                *polyline = polylines.add(Polyline { vertices });
+    for (entity, children, batched, tracker, debug, visibility) in shapes.iter_mut() {
+        if let Err(error) = debug.shape.validate() {
+            warn!(
+                "Not updating the invalid outline of {:?}: {}",
+                entity, error
+            );
+            continue;
+        }
+        let mut lines_children = children.iter().flat_map(|c| c.iter());
+        let has_lines = batched.is_some() || lines_children.any(|c| line_meshes.contains(*c));
+        if !has_lines {
+            // The shape was invalid when added, `insert_outline` handles the
+            // shapes added this frame.
+            if !tracker.is_added() {
+                assets.spawn_lines(&mut cmds, entity, debug, visibility);
+            }
+            continue;
+        }
+        if let Some(mut batched) = batched {
+            if !debug.depth_check {
+                // Lines drawn on top are never batched.
+                cmds.entity(entity).remove::<BatchedOutline>();
+                assets.spawn_lines(&mut cmds, entity, debug, visibility);
+            } else if batched.shape_version != debug.shape_version() {
+                batched.shape_version = debug.shape_version();
+                batched.strips = debug.shape.outline();
+            }
+        }
+        for child in children.iter().flat_map(|c| c.iter()) {
+            let mut line_mesh = match line_meshes.get_mut(*child) {
+                Ok(line_mesh) => line_mesh,
+                Err(_) => continue,
+            };
+            let shape_changed = line_mesh.shape_version != debug.shape_version();
+            line_mesh.shape_version = debug.shape_version();
+            if shape_changed {
+                cmds.entity(*child).insert(debug.shape.render_aabb());
+            }
+            match (
+                debug.depth_check,
+                lines.get_mut(*child),
+                on_top_lines.get_mut(*child),
+            ) {
+                (true, Ok((polyline, mut material)), _) => {
+                    let new_material = assets.polyline_material(debug);
+                    if *material != new_material {
+                        *material = new_material;
+                    }
+                    if shape_changed {
+                        if let Some(polyline) = assets.polylines.get_mut(polyline) {
+                            polyline.vertices = debug.shape.outline().polyline_vertices();
+                        }
+                    }
+                }
+                (false, _, Ok((mesh, mut material))) => {
+                    let new_material = assets.shape_material(debug.on_top_material());
+                    if *material != new_material {
+                        *material = new_material;
+                    }
+                    if shape_changed {
+                        if let Some(mesh) = assets.meshes.get_mut(mesh) {
+                            *mesh = debug.shape.outline_mesh();
+                        }
+                    }
+                }
+                _ => {
+                    // Depth checking was toggled, the lines must be rendered differently.
+                    cmds.entity(*child).despawn_recursive();
+                    assets.spawn_lines(&mut cmds, entity, debug, visibility);
+                }
             }
         }
     }
 }
 
-type OutlinesWithChangedVisibility = (Without<LineMesh>, With<ShapeOutline>, Changed<Visibility>);
+pub(crate) fn insert_fill(
+    mut cmds: Commands,
+    query: Query<(Entity, &ShapeFill, Option<&Visibility>), Added<ShapeFill>>,
+    mut assets: ShapeAssets,
+) {
+    for (entity, fill, visibility) in query.iter() {
+        assets.spawn_fill(&mut cmds, entity, fill, visibility);
+    }
+}
+type ChangedFill<'a> = (
+    Entity,
+    Option<&'a Children>,
+    ChangeTrackers<ShapeFill>,
+    &'a ShapeFill,
+    Option<&'a Visibility>,
+);
+/// Update the fill entities, see [`update_outline`].
+pub(crate) fn update_fill(
+    mut cmds: Commands,
+    mut fills: Query<(&mut FillMesh, &Handle<Mesh>, &mut Handle<ShapeMaterial>)>,
+    mut assets: ShapeAssets,
+    shapes: Query<ChangedFill, Changed<ShapeFill>>,
+) {
+    for (entity, children, tracker, fill, visibility) in shapes.iter() {
+        if let Err(error) = fill.shape.validate() {
+            warn!("Not updating the invalid fill of {:?}: {}", entity, error);
+            continue;
+        }
+        let children = children.iter().flat_map(|c| c.iter());
+        let mut has_fill = false;
+        for child in children {
+            if let Ok((mut fill_mesh, mesh, mut material)) = fills.get_mut(*child) {
+                has_fill = true;
+                let new_material = assets.shape_material(fill.material());
+                if *material != new_material {
+                    *material = new_material;
+                }
+                if fill_mesh.shape_version != fill.shape_version() {
+                    fill_mesh.shape_version = fill.shape_version();
+                    cmds.entity(*child).insert(fill.shape.render_aabb());
+                    if let Some(mesh) = assets.meshes.get_mut(mesh) {
+                        *mesh = fill.shape.fill_mesh();
+                    }
+                }
+            }
+        }
+        if !has_fill && !tracker.is_added() {
+            // The shape was invalid until now.
+            assets.spawn_fill(&mut cmds, entity, fill, visibility);
+        }
+    }
+}
+
+type ShapeMeshes = Or<(With<LineMesh>, With<FillMesh>)>;
+type ShapesWithChangedVisibility = (
+    Without<LineMesh>,
+    Without<FillMesh>,
+    Or<(With<ShapeOutline>, With<ShapeFill>)>,
+    Changed<Visibility>,
+);
 pub(crate) fn update_outlines_visibility(
-    mut lines: Query<&mut Visibility, With<LineMesh>>,
-    visibilities: Query<(&Children, &Visibility), OutlinesWithChangedVisibility>,
+    mut lines: Query<&mut Visibility, ShapeMeshes>,
+    visibilities: Query<(&Children, &Visibility), ShapesWithChangedVisibility>,
 ) {
     for (children, new_vis) in visibilities.iter() {
         for child in children.iter() {
@@ -104,10 +526,14 @@ pub(crate) fn update_outlines_visibility(
 pub(crate) fn remove_outline(
     mut cmds: Commands,
     lines: Query<(), With<LineMesh>>,
+    batched: Query<(), With<BatchedOutline>>,
     children: Query<&Children>,
     removed: RemovedComponents<ShapeOutline>,
 ) {
     for parent in removed.iter() {
+        if batched.get(parent).is_ok() {
+            cmds.entity(parent).remove::<BatchedOutline>();
+        }
         for child in children.get(parent).into_iter().flat_map(|p| &**p) {
             if lines.get(*child).is_ok() {
                 cmds.entity(*child).despawn();
@@ -115,3 +541,17 @@ pub(crate) fn remove_outline(
         }
     }
 }
+pub(crate) fn remove_fill(
+    mut cmds: Commands,
+    fills: Query<(), With<FillMesh>>,
+    children: Query<&Children>,
+    removed: RemovedComponents<ShapeFill>,
+) {
+    for parent in removed.iter() {
+        for child in children.get(parent).into_iter().flat_map(|p| &**p) {
+            if fills.get(*child).is_ok() {
+                cmds.entity(*child).despawn();
+            }
+        }
+    }
+}
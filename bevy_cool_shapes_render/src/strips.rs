@@ -0,0 +1,103 @@
+use std::iter;
+
+use bevy::math::Vec3;
+
+/// A list of line strips, each strip being a series of connected points.
+///
+/// All vertices are stored in a single buffer, [`LineStrips::vertices`],
+/// with [`LineStrips::offsets`] pointing to the start of each strip.
+///
+/// ```rust
+/// use bevy::math::Vec3;
+/// use bevy_cool_shapes_render::prelude::*;
+///
+/// let mut strips = LineStrips::new();
+/// strips.push_strip([Vec3::ZERO, Vec3::X, Vec3::Y]);
+/// strips.push_segment(Vec3::ZERO, Vec3::Z);
+/// assert_eq!(strips.strips().count(), 2);
+/// assert_eq!(strips.segments().count(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineStrips {
+    vertices: Vec<Vec3>,
+    offsets: Vec<usize>,
+}
+impl LineStrips {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a strip connecting all points of `strip` in order.
+    ///
+    /// Strips with less than two points are ignored.
+    pub fn push_strip(&mut self, strip: impl IntoIterator<Item = Vec3>) {
+        let offset = self.vertices.len();
+        self.vertices.extend(strip);
+        if self.vertices.len() - offset < 2 {
+            self.vertices.truncate(offset);
+        } else {
+            self.offsets.push(offset);
+        }
+    }
+    /// Add a strip of a single line segment.
+    pub fn push_segment(&mut self, start: Vec3, end: Vec3) {
+        self.push_strip([start, end]);
+    }
+    /// Add each `[start, end]` segment as its own strip.
+    pub fn push_segments(&mut self, segments: impl IntoIterator<Item = [Vec3; 2]>) {
+        for [start, end] in segments {
+            self.push_segment(start, end);
+        }
+    }
+    /// Add all the strips of `other`.
+    pub fn append(&mut self, other: &LineStrips) {
+        let offset = self.vertices.len();
+        self.vertices.extend_from_slice(&other.vertices);
+        self.offsets
+            .extend(other.offsets.iter().map(|o| o + offset));
+    }
+    /// Apply `f` to all vertices.
+    pub fn map_vertices(&mut self, mut f: impl FnMut(Vec3) -> Vec3) {
+        for vertex in &mut self.vertices {
+            *vertex = f(*vertex);
+        }
+    }
+    /// The vertices of all strips, one after the other.
+    pub fn vertices(&self) -> &[Vec3] {
+        &self.vertices
+    }
+    /// The index in [`LineStrips::vertices`] of the first point of each strip.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+    /// How many strips there are.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+    /// Iterate over the points of each strip.
+    pub fn strips(&self) -> impl Iterator<Item = &[Vec3]> + '_ {
+        let ends = self.offsets.iter().skip(1).copied();
+        let ends = ends.chain(iter::once(self.vertices.len()));
+        let ranges = self.offsets.iter().zip(ends);
+        ranges.map(|(start, end)| &self.vertices[*start..end])
+    }
+    /// Iterate over all line segments, as `[start, end]`.
+    pub fn segments(&self) -> impl Iterator<Item = [Vec3; 2]> + '_ {
+        let segments = self.strips().flat_map(|strip| strip.windows(2));
+        segments.map(|w| [w[0], w[1]])
+    }
+    /// The vertices of all strips separated by `Vec3::NAN`, as expected by
+    /// `bevy_polyline`.
+    pub fn polyline_vertices(&self) -> Vec<Vec3> {
+        let mut vertices = Vec::with_capacity(self.vertices.len() + self.offsets.len());
+        for strip in self.strips() {
+            if !vertices.is_empty() {
+                vertices.push(Vec3::NAN);
+            }
+            vertices.extend_from_slice(strip);
+        }
+        vertices
+    }
+}
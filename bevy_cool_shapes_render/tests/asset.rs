@@ -0,0 +1,27 @@
+#![cfg(feature = "asset")]
+
+use bevy::prelude::*;
+use bevy_cool_shapes_render::prelude::*;
+
+#[test]
+fn example_asset() {
+    let asset: ShapeAsset = ron::from_str(include_str!("../assets/capsule.shape.ron")).unwrap();
+    let outline = asset.outline();
+    assert_eq!(outline.color(), Color::rgb(0.0, 1.0, 1.0));
+    assert_eq!(outline.width(), 200.0);
+    assert_eq!(outline.density(), 8);
+    assert_eq!(outline.shape().aabb().max, Vec3::new(2.0, 4.0, 2.0));
+}
+
+#[test]
+fn default_style() {
+    let asset: ShapeAsset = ron::from_str(
+        "(shape: (density: 4, shape: Shape2d(Disc((radius: 1.0)))), depth_check: false)",
+    )
+    .unwrap();
+    let outline = asset.outline();
+    let default = ShapeOutline::default();
+    assert_eq!(outline.color(), default.color());
+    assert_eq!(outline.width(), default.width());
+    assert!(!outline.depth_check());
+}
@@ -0,0 +1,78 @@
+use bevy::math::{Vec2, Vec3};
+use bevy_cool_shapes_render::prelude::*;
+
+const EPSILON: f32 = 1e-4;
+
+/// Check that all the outline vertices of `shape` are within `aabb` and
+/// `sphere`.
+fn check(shape: OutlineableShape, aabb: Aabb, sphere: BoundingSphere) {
+    let outline = shape.outline();
+    assert!(!outline.is_empty(), "{shape:?}");
+    for &vertex in outline.vertices() {
+        let in_aabb = vertex.cmpge(aabb.min - EPSILON) & vertex.cmple(aabb.max + EPSILON);
+        assert!(in_aabb.all(), "{vertex} out of {aabb:?} for {shape:?}");
+        let in_sphere = vertex.distance(sphere.center) <= sphere.radius + EPSILON;
+        assert!(in_sphere, "{vertex} out of {sphere:?} for {shape:?}");
+    }
+}
+macro_rules! check {
+    ($( $shape:expr ),* $(,)?) => {$({
+        let shape = $shape;
+        let (aabb, sphere) = (shape.aabb(), shape.bounding_sphere());
+        check(OutlineableShape::from(shape).with_density(8), aabb, sphere);
+    })*};
+}
+
+#[test]
+fn outlines_are_bounded_2d() {
+    let triangle = Triangle {
+        a: Vec2::new(-1.0, 0.5),
+        b: Vec2::new(3.0, 0.0),
+        c: Vec2::new(0.0, 2.0),
+    };
+    let points = vec![Vec2::ZERO, Vec2::X * 2.0, Vec2::ONE, Vec2::new(0.5, 3.0)];
+    check![
+        Disc { radius: 2.0 },
+        HalfDisc { radius: 2.0 },
+        QuarterDisc { radius: 2.0 },
+        Annulus { inner_radius: 1.0, outer_radius: 2.0 },
+        Ellipse { half_axes: Vec2::new(3.0, 1.0) },
+        Rectangle { size: Vec2::new(1.0, 3.0) },
+        RoundedRectangle { size: Vec2::new(1.0, 3.0), bevel: 0.25 },
+        triangle.clone(),
+        Polygon::new(points),
+    ];
+    let base = Shape2d::Triangle(triangle);
+    let quarter = Shape2d::QuarterDisc(QuarterDisc { radius: 1.0 });
+    let half = Shape2d::HalfDisc(HalfDisc { radius: 1.0 });
+    check![
+        Pyramid { base: base.clone(), height: 2.0 },
+        Extruded { base, height: 2.0 },
+        Pyramid { base: quarter, height: 2.0 },
+        Extruded { base: half, height: 2.0 },
+    ];
+}
+
+#[test]
+fn outlines_are_bounded_3d() {
+    let lines = Lines {
+        points: vec![Vec3::ZERO, Vec3::new(1.0, -2.0, 3.0), Vec3::Y],
+        lines: vec![[0, 1], [1, 2]],
+    };
+    let heights = vec![vec![0.0, 1.0, -2.0], vec![3.0, 0.5, 0.0]];
+    let (a, b, c, d) = (Vec3::ZERO, Vec3::X, Vec3::new(0.0, 2.0, 1.0), -Vec3::Z);
+    check![
+        Sphere { radius: 2.0 },
+        HalfSphere { radius: 2.0 },
+        Capsule { radius: 1.0, segment_height: 3.0 },
+        Cuboid { size: Vec3::new(1.0, 2.0, 3.0) },
+        RoundedCuboid { size: Vec3::new(1.0, 2.0, 3.0), bevel: 0.25 },
+        Cone { height: 3.0, base_radius: 1.0 },
+        Tetrahedron { a, b, c, d },
+        lines,
+        Cylinder { height: 3.0, radius: 1.0 },
+        Torus { major_radius: 2.0, minor_radius: 0.5 },
+        Ellipsoid { half_axes: Vec3::new(1.0, 2.0, 3.0) },
+        HeightField { heights, size: Vec2::new(2.0, 3.0) },
+    ];
+}
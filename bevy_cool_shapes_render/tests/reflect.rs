@@ -0,0 +1,45 @@
+#![cfg(feature = "reflect")]
+
+use bevy::prelude::*;
+use bevy::reflect::{DynamicStruct, GetPath};
+use bevy_cool_shapes_render::prelude::*;
+
+#[test]
+fn edit_outline_shape() {
+    let capsule = Capsule { radius: 1.0, segment_height: 2.0 };
+    let mut outline = OutlineableShape::from(capsule).lines(Color::RED, 1.0, 0.0);
+    *outline.get_path_mut::<f32>("shape.capsule.radius").unwrap() = 3.0;
+    *outline.get_path_mut::<usize>("shape.density").unwrap() = 8;
+    assert_eq!(outline.get_path::<f32>("shape.capsule.radius"), Ok(&3.0));
+    assert_eq!(outline.density(), 8);
+    assert!(outline.path("shape.sphere").is_err());
+
+    let disc: OutlineableShape = Disc { radius: 2.0 }.into();
+    assert_eq!(disc.get_path::<f32>("shape_2d.disc.radius"), Ok(&2.0));
+}
+
+#[test]
+fn apply_other_shape() {
+    let mut fill = OutlineableShape::from(Sphere { radius: 1.0 }).fill(Color::RED);
+    let mut cuboid = DynamicStruct::default();
+    cuboid.insert("size", Vec3::ONE);
+    let mut shape = DynamicStruct::default();
+    shape.insert("cuboid", cuboid);
+    let mut patch = DynamicStruct::default();
+    patch.insert("shape", shape);
+
+    fill.apply(&patch);
+    assert_eq!(fill.get_path::<Vec3>("shape.cuboid.size"), Ok(&Vec3::ONE));
+    assert!(fill.path("shape.sphere").is_err());
+    assert_eq!(fill.shape().aabb().max, Vec3::splat(0.5));
+}
+
+#[test]
+fn apply_non_struct_shape() {
+    let mut fill = OutlineableShape::from(Sphere { radius: 1.0 }).fill(Color::RED);
+    let mut patch = DynamicStruct::default();
+    patch.insert("shape", 2.0_f32);
+
+    fill.apply(&patch);
+    assert_eq!(fill.get_path::<f32>("shape.sphere.radius"), Ok(&1.0));
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "serde")]
+
+use bevy::prelude::*;
+use bevy_cool_shapes_render::prelude::*;
+
+fn shapes() -> Vec<OutlineableShape> {
+    let base = Shape2d::RoundedRectangle(RoundedRectangle { size: Vec2::ONE, bevel: 0.1 });
+    vec![
+        Disc { radius: 1.0 }.into(),
+        Shape2d::Rectangle(Rectangle { size: Vec2::ONE }).into(),
+        Pyramid { base: base.clone(), height: 1.0 }.into(),
+        Extruded { base, height: 1.0 }.into(),
+        Sphere { radius: 1.0 }.into(),
+        HalfSphere { radius: 1.0 }.into(),
+        Capsule { radius: 1.0, segment_height: 1.0 }.into(),
+        Cuboid { size: Vec3::ONE }.into(),
+        RoundedCuboid { size: Vec3::ONE, bevel: 0.2 }.into(),
+        Cone { height: 1.0, base_radius: 1.0 }.into(),
+        Tetrahedron { a: Vec3::ZERO, b: Vec3::X, c: Vec3::Y, d: Vec3::Z }.into(),
+        Lines {
+            points: vec![Vec3::ZERO, Vec3::X],
+            lines: vec![[0, 1]],
+        }
+        .into(),
+        Cylinder { height: 1.0, radius: 1.0 }.into(),
+        Torus { major_radius: 1.0, minor_radius: 0.25 }.into(),
+        Ellipsoid { half_axes: Vec3::new(1.0, 2.0, 0.5) }.into(),
+        HeightField { heights: vec![vec![0.0; 2]; 2], size: Vec2::ONE }.into(),
+    ]
+}
+
+/// Serialize `value`, deserialize it and check that serializing it again
+/// gives the same result.
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: T) {
+    let serialized = ron::to_string(&value).unwrap();
+    let deserialized: T = ron::from_str(&serialized).unwrap();
+    assert_eq!(serialized, ron::to_string(&deserialized).unwrap());
+}
+
+#[test]
+fn outlineable_shapes_round_trip() {
+    for shape in shapes() {
+        round_trip(shape);
+    }
+}
+
+#[test]
+fn styles_round_trip() {
+    for shape in shapes() {
+        let outline = shape
+            .clone()
+            .lines(Color::CYAN, 3.0, 0.0)
+            .with_depth_check(false);
+        round_trip(outline);
+        round_trip(shape.fill(Color::rgba(1.0, 0.0, 0.0, 0.5)));
+    }
+    round_trip(ShapeOutline::default());
+    round_trip(ShapeFill::default());
+}
+
+#[test]
+fn custom_shapes_are_not_serialized() {
+    struct Custom;
+    impl Outlineable for Custom {
+        fn outline(&self, _: usize) -> LineStrips {
+            LineStrips::default()
+        }
+    }
+    assert!(ron::to_string(&OutlineableShape::custom(Custom)).is_err());
+}